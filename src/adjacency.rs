@@ -0,0 +1,393 @@
+//! Adjacency matrices, incidence matrices and edge lists.
+//!
+//! All formats list nodes in the order of [`Expr::nodes`], so a matrix can
+//! be moved to and from numerical tools, and an edge list to and from shell
+//! pipelines.
+//!
+//! Names with spaces, commas or quotes, like the nodes of a product, are
+//! quoted as in CSV, with any quotes inside doubled.
+//!
+//! ```grapl
+//! {X, [A, B]}
+//! => to_adjacency_matrix
+//! 0,0,1
+//! 0,0,1
+//! 1,1,0
+//! => to_incidence_matrix
+//! 1,0,1
+//! 0,1,1
+//! => to_edge_list
+//! A X
+//! B X
+//! ```
+
+use crate::{Expr, Graph, Node};
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+/// Adjacency matrix output configuration options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    delimiter: char,
+    header: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            delimiter: ',',
+            header: false,
+        }
+    }
+}
+
+impl Config {
+    /// Separate entries with tabs (TSV) instead of commas (CSV).
+    pub fn with_tabs(mut self) -> Self {
+        self.delimiter = '\t';
+        self
+    }
+
+    /// Start with a row of node names.
+    pub fn with_header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+}
+
+/// Errors that can occur reading an adjacency matrix or edge list.
+///
+/// Lines are numbered from 1.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A row of the matrix doesn't have an entry for each node.
+    Row(usize),
+    /// An entry of the matrix isn't `0` or `1`.
+    Entry(usize, String),
+    /// The matrix isn't symmetric, so it's not an undirected graph.
+    Asymmetric(Node, Node),
+    /// A line of the edge list has more than two nodes.
+    Edge(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Row(line) => write!(f, "line {}: wrong number of entries", line),
+            Error::Entry(line, entry) => write!(f, "line {}: invalid entry {:?}", line, entry),
+            Error::Asymmetric(a, b) => write!(f, "{} and {} aren't symmetric", a, b),
+            Error::Edge(line) => write!(f, "line {}: more than two nodes", line),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Expr {
+    /// Returns the 0/1 adjacency matrix of this expression's graph as CSV.
+    pub fn to_adjacency_matrix(&self) -> String {
+        self.to_adjacency_matrix_with(&Config::default())
+    }
+
+    /// Returns the 0/1 adjacency matrix of this expression's graph with the
+    /// given configuration.
+    pub fn to_adjacency_matrix_with(&self, config: &Config) -> String {
+        let graph = Graph::from(self);
+        let delimiter = config.delimiter.to_string();
+        let mut out = String::new();
+        if config.header {
+            let names: Vec<_> = graph.nodes().map(quote).collect();
+            writeln!(out, "{}", names.join(&delimiter)).unwrap();
+        }
+        for a in graph.nodes() {
+            let row: Vec<_> = graph
+                .nodes()
+                .map(|b| if graph.has_edge(a, b) { "1" } else { "0" })
+                .collect();
+            writeln!(out, "{}", row.join(&delimiter)).unwrap();
+        }
+        out
+    }
+
+    /// Returns the 0/1 incidence matrix of this expression's
+    /// [`hyperedges`](Expr::hyperedges) as CSV.
+    pub fn to_incidence_matrix(&self) -> String {
+        self.to_incidence_matrix_with(&Config::default())
+    }
+
+    /// Returns the 0/1 incidence matrix of this expression's
+    /// [`hyperedges`](Expr::hyperedges) with the given configuration.
+    ///
+    /// There is a row for each hyperedge and a column for each node, so the
+    /// header names the nodes just like an adjacency matrix. Row `i` is the
+    /// hyperedge named `ei` in the [`incidence_graph`](Expr::incidence_graph).
+    pub fn to_incidence_matrix_with(&self, config: &Config) -> String {
+        let nodes = self.nodes();
+        let delimiter = config.delimiter.to_string();
+        let mut out = String::new();
+        if config.header {
+            let names: Vec<_> = nodes.iter().map(quote).collect();
+            writeln!(out, "{}", names.join(&delimiter)).unwrap();
+        }
+        for hyperedge in self.hyperedges() {
+            // Both lists are sorted, so membership is a binary search.
+            let row: Vec<_> = nodes
+                .iter()
+                .map(|n| match hyperedge.binary_search(n) {
+                    Ok(_) => "1",
+                    Err(_) => "0",
+                })
+                .collect();
+            writeln!(out, "{}", row.join(&delimiter)).unwrap();
+        }
+        out
+    }
+
+    /// Parses a 0/1 adjacency matrix, separated by commas, tabs or spaces.
+    ///
+    /// Nodes are named by the first row when it isn't all 0s and 1s, and
+    /// `n0`, `n1`, ... otherwise. The diagonal is ignored since there are no
+    /// self-loops.
+    pub fn from_adjacency_matrix(input: &str) -> Result<Expr, Error> {
+        let mut rows = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, entries(line)))
+            .filter(|(_, entries)| !entries.is_empty())
+            .peekable();
+
+        let nodes: Vec<Node> = match rows.peek() {
+            Some((_, header)) if !header.iter().all(|e| e == "0" || e == "1") => {
+                let nodes = header.iter().map(|e| Node::from(e.as_str())).collect();
+                rows.next();
+                nodes
+            }
+            Some((_, row)) => (0..row.len())
+                .map(|i| Node::from(format!("n{}", i)))
+                .collect(),
+            None => vec![],
+        };
+
+        let mut matrix = vec![];
+        for (line, row) in rows {
+            if row.len() != nodes.len() {
+                return Err(Error::Row(line));
+            }
+            let row = row
+                .into_iter()
+                .map(|entry| match entry.as_str() {
+                    "0" => Ok(false),
+                    "1" => Ok(true),
+                    _ => Err(Error::Entry(line, entry)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            matrix.push((line, row));
+        }
+        if let Some((line, _)) = matrix.get(nodes.len()) {
+            return Err(Error::Row(*line));
+        } else if matrix.len() < nodes.len() {
+            return Err(Error::Row(input.lines().count() + 1));
+        }
+
+        let mut graph = Graph::new();
+        for (i, a) in nodes.iter().enumerate() {
+            graph.add_node(a.clone());
+            for (j, b) in nodes.iter().enumerate().skip(i + 1) {
+                match (matrix[i].1[j], matrix[j].1[i]) {
+                    (true, true) => graph.add_edge(a.clone(), b.clone()),
+                    (false, false) => {}
+                    _ => return Err(Error::Asymmetric(a.clone(), b.clone())),
+                }
+            }
+        }
+        Ok(Expr::from(&graph))
+    }
+
+    /// Returns the edge list of this expression's graph, with one `A B` edge
+    /// per line.
+    ///
+    /// Nodes without any edges are listed alone.
+    pub fn to_edge_list(&self) -> String {
+        let graph = Graph::from(self);
+        let mut out = String::new();
+        for a in graph.nodes() {
+            if graph.degree(a) == 0 {
+                writeln!(out, "{}", quote(a)).unwrap();
+            }
+            for b in graph.neighbors(a).filter(|b| a < *b) {
+                writeln!(out, "{} {}", quote(a), quote(b)).unwrap();
+            }
+        }
+        out
+    }
+
+    /// Parses a whitespace separated edge list.
+    ///
+    /// Lines with a single node add it without any edges, and blank lines or
+    /// lines starting with `#` are skipped.
+    pub fn from_edge_list(input: &str) -> Result<Expr, Error> {
+        let mut graph = Graph::new();
+        for (i, line) in input.lines().enumerate() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let mut nodes = split(line, char::is_whitespace).into_iter().map(Node::from);
+            match (nodes.next(), nodes.next(), nodes.next()) {
+                (None, _, _) => {}
+                (Some(a), None, _) => graph.add_node(a),
+                (Some(a), Some(b), None) => graph.add_edge(a, b),
+                _ => return Err(Error::Edge(i + 1)),
+            }
+        }
+        Ok(Expr::from(&graph))
+    }
+}
+
+fn entries(line: &str) -> Vec<String> {
+    split(line, |c| c == ',' || c.is_whitespace())
+}
+
+// Quotes names which would be split apart or read as a comment.
+fn quote(node: &Node) -> Cow<'_, str> {
+    let name = node.as_str();
+    if name.is_empty()
+        || name.starts_with('#')
+        || name.contains(|c: char| c == ',' || c == '"' || c.is_whitespace())
+    {
+        Cow::Owned(format!("\"{}\"", name.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+// Splits a line at runs of separators, keeping quoted entries whole.
+fn split(line: &str, separator: impl Fn(char) -> bool) -> Vec<String> {
+    let mut entries = vec![];
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| separator(*c)).is_some() {}
+        let Some(first) = chars.next() else {
+            return entries;
+        };
+        let mut entry = String::new();
+        if first == '"' {
+            while let Some(c) = chars.next() {
+                if c == '"' && chars.next_if_eq(&'"').is_none() {
+                    break;
+                }
+                entry.push(c);
+            }
+        } else {
+            entry.push(first);
+            while let Some(c) = chars.next_if(|c| !separator(*c)) {
+                entry.push(c);
+            }
+        }
+        entries.push(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Error};
+    use crate::{Expr, Graph, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn adjacency_matrix() {
+        let expr = Expr::parse("[{X, [A, B]}, C]").unwrap();
+        assert_eq!(
+            expr.to_adjacency_matrix(),
+            "0,0,0,1\n0,0,0,1\n0,0,0,0\n1,1,0,0\n"
+        );
+        let tsv = expr.to_adjacency_matrix_with(&Config::default().with_tabs().with_header());
+        assert_eq!(
+            tsv,
+            "A\tB\tC\tX\n0\t0\t0\t1\n0\t0\t0\t1\n0\t0\t0\t0\n1\t1\t0\t0\n"
+        );
+
+        let read = Expr::from_adjacency_matrix(&tsv).unwrap();
+        assert_eq!(read.nodes(), expr.nodes());
+        assert_eq!(read.edges(), expr.edges());
+        assert_eq!(
+            Expr::from_adjacency_matrix("0 1\n1 1\n").unwrap(),
+            Expr::parse("{n0, n1}").unwrap()
+        );
+        assert_eq!(
+            Expr::from_adjacency_matrix("").unwrap(),
+            Expr::parse("[]").unwrap()
+        );
+    }
+
+    #[test]
+    fn adjacency_matrix_errors() {
+        assert_eq!(Expr::from_adjacency_matrix("0,1\n1\n"), Err(Error::Row(2)));
+        assert_eq!(
+            Expr::from_adjacency_matrix("0,1\n1,0\n0,0\n"),
+            Err(Error::Row(3))
+        );
+        assert_eq!(
+            Expr::from_adjacency_matrix("A,B\n0,1\n"),
+            Err(Error::Row(3))
+        );
+        assert_eq!(
+            Expr::from_adjacency_matrix("A,B\n0,2\n1,0\n"),
+            Err(Error::Entry(2, "2".into()))
+        );
+        assert_eq!(
+            Expr::from_adjacency_matrix("A,B\n0,1\n0,0\n"),
+            Err(Error::Asymmetric(Node::from("A"), Node::from("B")))
+        );
+    }
+
+    #[test]
+    fn incidence_matrix() {
+        let expr = Expr::parse("[{X, [A, B]}, {A, B, C}, D]").unwrap();
+        assert_eq!(
+            expr.to_incidence_matrix(),
+            "1,1,1,0,0\n1,0,0,0,1\n0,1,0,0,1\n"
+        );
+        assert_eq!(
+            expr.to_incidence_matrix_with(&Config::default().with_tabs().with_header()),
+            "A\tB\tC\tD\tX\n1\t1\t1\t0\t0\n1\t0\t0\t0\t1\n0\t1\t0\t0\t1\n"
+        );
+        assert_eq!(Expr::parse("[A, B]").unwrap().to_incidence_matrix(), "");
+    }
+
+    #[test]
+    fn edge_list() {
+        let expr = Expr::parse("[{X, [A, B]}, C]").unwrap();
+        assert_eq!(expr.to_edge_list(), "A X\nB X\nC\n");
+        assert_eq!(
+            Expr::from_edge_list("# star\nX A\n\n  X\tB\nC\n").unwrap(),
+            Expr::from_edge_list(&expr.to_edge_list()).unwrap()
+        );
+        assert_eq!(Expr::from_edge_list("A B C"), Err(Error::Edge(1)));
+    }
+
+    #[test]
+    fn quoted_names() {
+        let expr = Expr::parse("{A, B} * {X, Y}").unwrap();
+        assert_eq!(
+            expr.to_edge_list(),
+            "\"(A, X)\" \"(A, Y)\"\n\"(A, X)\" \"(B, X)\"\n\"(A, Y)\" \"(B, Y)\"\n\"(B, X)\" \"(B, Y)\"\n"
+        );
+        let read = Expr::from_edge_list(&expr.to_edge_list()).unwrap();
+        assert_eq!(read.nodes(), expr.nodes());
+        assert_eq!(read.edges(), expr.edges());
+
+        for config in [Config::default(), Config::default().with_tabs()] {
+            let matrix = expr.to_adjacency_matrix_with(&config.with_header());
+            let read = Expr::from_adjacency_matrix(&matrix).unwrap();
+            assert_eq!(read.nodes(), expr.nodes());
+            assert_eq!(read.edges(), expr.edges());
+        }
+
+        let graph: Graph = [(Node::from("say \"hi\""), Node::from("#1"))]
+            .into_iter()
+            .collect();
+        let list = Expr::from(&graph).to_edge_list();
+        assert_eq!(list, "\"#1\" \"say \"\"hi\"\"\"\n");
+        assert_eq!(Expr::from_edge_list(&list), Ok(Expr::from(&graph)));
+    }
+}
@@ -0,0 +1,238 @@
+//! Graph algorithms on the graph of an expression.
+//!
+//! Results are sorted by node wherever the algorithm leaves a choice, so the
+//! same expression always gives the same answer.
+//!
+//! ```grapl
+//! [{X, [A, B]}, {C, D}]
+//! => components
+//! {[A, B], X}
+//! {C, D}
+//! => shortest_path A B
+//! A X B
+//! => coloring
+//! A B D
+//! C X
+//! ```
+
+use crate::{Expr, Graph, Node};
+use petgraph::algo::{astar, dsatur_coloring};
+use petgraph::graph::UnGraph;
+use std::collections::{BTreeMap, VecDeque};
+
+impl Expr {
+    /// Returns the connected components of this expression's graph, ordered
+    /// by their smallest node.
+    pub fn components(&self) -> Vec<Expr> {
+        let graph = Graph::from(self);
+        graph
+            .components()
+            .iter()
+            .map(|component| Expr::from(&graph.subgraph(component)))
+            .collect()
+    }
+
+    /// Returns a shortest path between two nodes, including both ends, or
+    /// `None` if they aren't connected.
+    pub fn shortest_path(&self, from: &Node, to: &Node) -> Option<Vec<Node>> {
+        let graph = UnGraph::<Node, ()>::from(self);
+        let start = graph.node_indices().find(|i| graph[*i] == *from)?;
+        let (_, path) = astar(&graph, start, |i| graph[i] == *to, |_| 1, |_| 0)?;
+        Some(path.into_iter().map(|i| graph[i].clone()).collect())
+    }
+
+    /// Returns the number of edges of each node.
+    pub fn degrees(&self) -> BTreeMap<Node, usize> {
+        let graph = Graph::from(self);
+        graph
+            .nodes()
+            .map(|node| (node.clone(), graph.degree(node)))
+            .collect()
+    }
+
+    /// Splits the nodes into two parts without any edges inside either part,
+    /// or returns `None` if the graph has an odd cycle.
+    ///
+    /// The smallest node of each component is in the first part.
+    pub fn bipartition(&self) -> Option<(Vec<Node>, Vec<Node>)> {
+        let graph = Graph::from(self);
+        let (depths, _) = forest(&graph);
+        if graph.edges().any(|(a, b)| depths[a] % 2 == depths[b] % 2) {
+            return None;
+        }
+        let (even, odd) = graph.nodes().cloned().partition(|n| depths[n] % 2 == 0);
+        Some((even, odd))
+    }
+
+    /// Returns a cycle basis of this expression's graph: every cycle can be
+    /// made out of these cycles.
+    ///
+    /// Each cycle closes a breadth-first spanning forest, and starts at the
+    /// smaller node of the edge it adds to the forest.
+    pub fn cycles(&self) -> Vec<Vec<Node>> {
+        let graph = Graph::from(self);
+        let (depths, parents) = forest(&graph);
+        let mut cycles = vec![];
+        for (a, b) in graph.edges() {
+            if parents.get(a) == Some(&b) || parents.get(b) == Some(&a) {
+                continue;
+            }
+            // Walk up from both ends until they meet.
+            let (mut left, mut right) = (vec![a], vec![b]);
+            let (mut x, mut y) = (a, b);
+            while x != y {
+                if depths[x] >= depths[y] {
+                    x = parents[x];
+                    left.push(x);
+                } else {
+                    y = parents[y];
+                    right.push(y);
+                }
+            }
+            right.pop();
+            left.extend(right.into_iter().rev());
+            cycles.push(left.into_iter().cloned().collect());
+        }
+        cycles
+    }
+
+    /// Returns a proper coloring of this expression's graph as classes of
+    /// nodes which share a color, using the DSatur heuristic. Classes are
+    /// ordered by their smallest node.
+    ///
+    /// The coloring isn't always minimal, but it is for bipartite graphs,
+    /// cycles and other common cases.
+    pub fn coloring(&self) -> Vec<Vec<Node>> {
+        let graph = UnGraph::<Node, ()>::from(self);
+        let (colors, count) = dsatur_coloring(&graph);
+        let mut classes = vec![vec![]; count];
+        // Node indices are in sorted order, so each class is sorted.
+        for i in graph.node_indices() {
+            classes[colors[&i]].push(graph[i].clone());
+        }
+        classes.retain(|class| !class.is_empty());
+        classes.sort_unstable();
+        classes
+    }
+}
+
+// A breadth-first spanning forest, rooting each component at its smallest
+// node. Returns the depth of each node, and the parent of each non-root.
+fn forest(graph: &Graph) -> (BTreeMap<&Node, usize>, BTreeMap<&Node, &Node>) {
+    let mut depths = BTreeMap::new();
+    let mut parents = BTreeMap::new();
+    for root in graph.nodes() {
+        if depths.contains_key(root) {
+            continue;
+        }
+        depths.insert(root, 0);
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            let depth = depths[node] + 1;
+            for neighbor in graph.neighbors(node) {
+                if !depths.contains_key(neighbor) {
+                    depths.insert(neighbor, depth);
+                    parents.insert(neighbor, node);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    (depths, parents)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn components() {
+        let expr = Expr::parse("[{X, [A, B]}, {C, D}, E]").unwrap();
+        assert_eq!(
+            expr.components(),
+            vec![
+                Expr::parse("{[A, B], X}").unwrap(),
+                Expr::parse("{C, D}").unwrap(),
+                Expr::parse("E").unwrap(),
+            ]
+        );
+        assert_eq!(Expr::parse("[]").unwrap().components(), vec![]);
+    }
+
+    #[test]
+    fn shortest_path() {
+        let expr = Expr::parse("[{A, B}, {B, C}, {C, D}, {A, X}, {X, D}, E]").unwrap();
+        let path = |a: &str, b: &str| expr.shortest_path(&Node::from(a), &Node::from(b));
+        assert_eq!(
+            path("A", "D"),
+            Some(vec![Node::from("A"), Node::from("X"), Node::from("D")])
+        );
+        assert_eq!(path("A", "A"), Some(vec![Node::from("A")]));
+        assert_eq!(path("A", "E"), None);
+        assert_eq!(path("A", "Z"), None);
+        assert_eq!(path("Z", "A"), None);
+    }
+
+    #[test]
+    fn degrees() {
+        let degrees = Expr::parse("[{X, [A, B]}, C]").unwrap().degrees();
+        assert_eq!(
+            degrees.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Node::from("A"), 1),
+                (Node::from("B"), 1),
+                (Node::from("C"), 0),
+                (Node::from("X"), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn bipartition() {
+        assert_eq!(
+            Expr::parse("[{[A, B], [X, Y]}, {C, D}]")
+                .unwrap()
+                .bipartition(),
+            Some((
+                vec![Node::from("A"), Node::from("B"), Node::from("C")],
+                vec![Node::from("D"), Node::from("X"), Node::from("Y")]
+            ))
+        );
+        assert_eq!(Expr::parse("{A, B, C}").unwrap().bipartition(), None);
+    }
+
+    #[test]
+    fn cycles() {
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().cycles(),
+            vec![] as Vec<Vec<Node>>
+        );
+        assert_eq!(
+            Expr::parse("[{A, B, C}, {C, D}, {D, E}, {E, A}]")
+                .unwrap()
+                .cycles(),
+            vec![
+                vec![Node::from("B"), Node::from("A"), Node::from("C")],
+                vec![
+                    Node::from("D"),
+                    Node::from("C"),
+                    Node::from("A"),
+                    Node::from("E")
+                ]
+            ]
+        );
+    }
+
+    #[test]
+    fn coloring() {
+        assert_eq!(
+            Expr::parse("[{X, [A, B]}, {C, D}]").unwrap().coloring(),
+            vec![
+                vec![Node::from("A"), Node::from("B"), Node::from("D")],
+                vec![Node::from("C"), Node::from("X")]
+            ]
+        );
+        assert_eq!(Expr::parse("{A, B, C}").unwrap().coloring().len(), 3);
+    }
+}
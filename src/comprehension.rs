@@ -0,0 +1,388 @@
+//! Comprehensions, which generate an expression for each combination of nodes.
+//!
+//! Each generator `x in G` iterates over the nodes of `G`, and the body is
+//! copied with the variables replaced by their nodes, for each combination
+//! which meets all of the conditions. Square brackets collect the copies into
+//! a disconnected expression, and braces into a connected one:
+//!
+//! ```grapl
+//! G = [A, B, C]
+//! [{x, y} for x in G, y in G if x < y]
+//! => [{A, B}, {A, C}, {B, C}]
+//! {x for x in web[1..3] if x != web2}
+//! => {web1, web3}
+//! ```
+//!
+//! Generators are evaluated during [`Resolve`](crate::Resolve), so they can
+//! iterate over the nodes of bound graphs. Without an environment, a
+//! generator over a name iterates over just that node.
+
+use crate::limit::{Budget, LimitExceeded};
+use crate::{Expr, Node};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A generated expression, e.g. `[{x, y} for x in G1, y in G2 if x != y]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comprehension {
+    connected: bool,
+    body: Expr,
+    generators: Vec<(Node, Expr)>,
+    conditions: Vec<Condition>,
+}
+
+/// A comparison between two nodes which filters a [`Comprehension`].
+///
+/// Either side can be a variable or a node, and nodes are ordered by name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Condition {
+    /// `x == y`
+    Eq(Node, Node),
+    /// `x != y`
+    Ne(Node, Node),
+    /// `x < y`
+    Lt(Node, Node),
+}
+
+impl Condition {
+    fn holds(&self, vars: &BTreeMap<Node, Node>) -> bool {
+        let value = |node| vars.get(node).unwrap_or(node);
+        match self {
+            Condition::Eq(a, b) => value(a) == value(b),
+            Condition::Ne(a, b) => value(a) != value(b),
+            Condition::Lt(a, b) => value(a) < value(b),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, op, b) = match self {
+            Condition::Eq(a, b) => (a, "==", b),
+            Condition::Ne(a, b) => (a, "!=", b),
+            Condition::Lt(a, b) => (a, "<", b),
+        };
+        write!(
+            f,
+            "{} {} {}",
+            Expr::Node(a.clone()),
+            op,
+            Expr::Node(b.clone())
+        )
+    }
+}
+
+impl Comprehension {
+    /// Create a comprehension collecting a copy of the body for each
+    /// combination of nodes from the generators which meets the conditions.
+    pub fn new(
+        connected: bool,
+        body: Expr,
+        generators: Vec<(Node, Expr)>,
+        conditions: Vec<Condition>,
+    ) -> Self {
+        Comprehension {
+            connected,
+            body,
+            generators,
+            conditions,
+        }
+    }
+
+    /// Returns true if the copies of the body are connected to each other.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// The expression copied for each combination of nodes.
+    pub fn body(&self) -> &Expr {
+        &self.body
+    }
+
+    /// Each variable with the expression whose nodes it iterates over.
+    pub fn generators(&self) -> &[(Node, Expr)] {
+        &self.generators
+    }
+
+    /// The conditions every combination of nodes must meet.
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    pub(crate) fn body_mut(&mut self) -> &mut Expr {
+        &mut self.body
+    }
+
+    // The expressions iterated over by each generator.
+    pub(crate) fn sources_mut(&mut self) -> impl Iterator<Item = &mut Expr> {
+        self.generators.iter_mut().map(|(_, source)| source)
+    }
+
+    /// Returns true if the given node appears anywhere in this comprehension.
+    pub(crate) fn contains(&self, node: &Node) -> bool {
+        self.body.contains(node) || self.generators.iter().any(|(_, e)| e.contains(node))
+    }
+
+    /// An upper bound on the size of the expansion, ignoring conditions.
+    ///
+    /// The size of each source bounds its number of nodes, without expanding
+    /// it.
+    pub(crate) fn size(&self) -> usize {
+        self.generators
+            .iter()
+            .fold(self.body.size(), |size, (_, e)| {
+                size.saturating_mul(e.size())
+            })
+    }
+
+    /// Expands this comprehension into a connected or disconnected
+    /// expression, within the given budget.
+    pub(crate) fn expand(&self, budget: &Budget) -> Result<Expr, LimitExceeded> {
+        let sources = self
+            .generators
+            .iter()
+            .map(|(_, e)| e.nodes_within(budget))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut exprs = vec![];
+        let mut size = 0;
+        self.generate(
+            0,
+            &sources,
+            &mut BTreeMap::new(),
+            &mut exprs,
+            &mut size,
+            budget,
+        )?;
+        Ok(if self.connected {
+            Expr::Connected(exprs)
+        } else {
+            Expr::Disconnected(exprs)
+        })
+    }
+
+    // Assign each node of the `i`th generator in turn, and add a copy of the
+    // body once every generator is assigned. A repeated variable is assigned
+    // again by its later generator.
+    fn generate(
+        &self,
+        i: usize,
+        sources: &[Vec<Node>],
+        vars: &mut BTreeMap<Node, Node>,
+        exprs: &mut Vec<Expr>,
+        size: &mut usize,
+        budget: &Budget,
+    ) -> Result<(), LimitExceeded> {
+        let Some(((var, _), nodes)) = self.generators.get(i).zip(sources.get(i)) else {
+            budget.step()?;
+            if self.conditions.iter().all(|c| c.holds(vars)) {
+                let expr = self.body.substitute(vars);
+                *size = size.saturating_add(expr.size());
+                budget.nodes(*size)?;
+                exprs.push(expr);
+            }
+            return Ok(());
+        };
+        for node in nodes {
+            vars.insert(var.clone(), node.clone());
+            self.generate(i + 1, sources, vars, exprs, size, budget)?;
+        }
+        vars.remove(var);
+        Ok(())
+    }
+}
+
+impl fmt::Display for Comprehension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (open, close) = if self.connected {
+            ('{', '}')
+        } else {
+            ('[', ']')
+        };
+        let generators: Vec<_> = self
+            .generators
+            .iter()
+            .map(|(var, source)| format!("{} in {}", Expr::Node(var.clone()), source))
+            .collect();
+        write!(f, "{}{} for {}", open, self.body, generators.join(", "))?;
+        if !self.conditions.is_empty() {
+            let conditions: Vec<_> = self.conditions.iter().map(|c| c.to_string()).collect();
+            write!(f, " if {}", conditions.join(" and "))?;
+        }
+        write!(f, "{}", close)
+    }
+}
+
+impl Expr {
+    // Replaces the variables with their nodes, except where a nested
+    // comprehension binds the same variable.
+    fn substitute(&self, vars: &BTreeMap<Node, Node>) -> Expr {
+        let all = |exprs: &[Expr]| exprs.iter().map(|e| e.substitute(vars)).collect();
+        match self {
+            Expr::Node(node) => Expr::Node(vars.get(node).unwrap_or(node).clone()),
+            Expr::Connected(exprs) => Expr::Connected(all(exprs)),
+            Expr::Disconnected(exprs) => Expr::Disconnected(all(exprs)),
+            Expr::Path(exprs) => Expr::Path(all(exprs)),
+            Expr::Cycle(exprs) => Expr::Cycle(all(exprs)),
+            Expr::Product(product, a, b) => Expr::Product(
+                *product,
+                Box::new(a.substitute(vars)),
+                Box::new(b.substitute(vars)),
+            ),
+            Expr::Subgraph(subgraph, a, b) => Expr::Subgraph(
+                *subgraph,
+                Box::new(a.substitute(vars)),
+                Box::new(b.substitute(vars)),
+            ),
+            Expr::Comprehension(c) => {
+                let mut inner = vars.clone();
+                let generators = c
+                    .generators
+                    .iter()
+                    .map(|(var, source)| {
+                        let source = source.substitute(&inner);
+                        inner.remove(var);
+                        (var.clone(), source)
+                    })
+                    .collect();
+                let value = |node: &Node| inner.get(node).unwrap_or(node).clone();
+                let conditions = c
+                    .conditions
+                    .iter()
+                    .map(|condition| match condition {
+                        Condition::Eq(a, b) => Condition::Eq(value(a), value(b)),
+                        Condition::Ne(a, b) => Condition::Ne(value(a), value(b)),
+                        Condition::Lt(a, b) => Condition::Lt(value(a), value(b)),
+                    })
+                    .collect();
+                Expr::Comprehension(Box::new(Comprehension {
+                    connected: c.connected,
+                    body: c.body.substitute(&inner),
+                    generators,
+                    conditions,
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comprehension, Condition};
+    use crate::limit::{Limit, LimitExceeded, Limits};
+    use crate::resolve::{Config, Env};
+    use crate::{Expr, Node, Normalize, Parse, Resolve};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_comprehension() {
+        let expr = Expr::parse("[ {x, y} for x in G1, y in G2 if x != y ]").unwrap();
+        assert_eq!(
+            expr,
+            Expr::Comprehension(Box::new(Comprehension::new(
+                false,
+                Expr::parse("{x, y}").unwrap(),
+                vec![
+                    (Node::from("x"), Expr::parse("G1").unwrap()),
+                    (Node::from("y"), Expr::parse("G2").unwrap()),
+                ],
+                vec![Condition::Ne(Node::from("x"), Node::from("y"))],
+            )))
+        );
+        assert_eq!(expr.to_string(), "[{x, y} for x in G1, y in G2 if x != y]");
+        let expr = "{x for x in [A, B] * C if x < y and x == B}";
+        assert_eq!(Expr::parse(expr).unwrap().to_string(), expr);
+
+        // Keywords are only special after the body.
+        assert_eq!(
+            Expr::parse("[for, in, if]").unwrap().to_string(),
+            "[for, in, if]"
+        );
+        assert!(Expr::parse("[x for x]").has_errors());
+        assert!(Expr::parse("[x, y for x in G]").has_errors());
+    }
+
+    #[test]
+    fn parse_deep_nesting() {
+        // Each group is parsed once, whether or not it has a `for` suffix.
+        let depth = 200;
+        let nested = format!("{}A{}", "[{".repeat(depth), "}]".repeat(depth));
+        assert_eq!(Expr::parse(&nested).unwrap().to_string(), nested);
+
+        let inner = format!("{}x{}", "[".repeat(depth), "]".repeat(depth));
+        let expr = Expr::parse(&format!("{{{} for x in [A, B]}}", inner)).unwrap();
+        assert!(matches!(expr, Expr::Comprehension(_)));
+        assert_eq!(expr.normalize(), Expr::parse("{A, B}").unwrap());
+    }
+
+    #[test]
+    fn normalize_comprehension() {
+        assert_eq!(
+            Expr::parse("[{x, y} for x in [A, B, C], y in [A, B, C] if x < y]")
+                .unwrap()
+                .normalize(),
+            Expr::parse("[{A, B}, {A, C}, {B, C}]").unwrap()
+        );
+        assert_eq!(
+            Expr::parse("{x for x in web[1..3] if x != web2}")
+                .unwrap()
+                .normalize(),
+            Expr::parse("{web1, web3}").unwrap()
+        );
+        assert_eq!(
+            Expr::parse("[{hub, x} for x in [A, B]]").unwrap().nodes(),
+            Expr::parse("[A, B, hub]").unwrap().nodes()
+        );
+        assert_eq!(
+            Expr::parse("[{x, y} for x in [A, B, C], y in [A, B, C]]")
+                .unwrap()
+                .try_nodes_with(&Limits::default().with_nodes(10)),
+            Err(LimitExceeded(Limit::Nodes))
+        );
+        // Later generators can bind the same variable again.
+        assert_eq!(
+            Expr::parse("[{x, y} for x in [A, B], x in [C], y in [D, E]]")
+                .unwrap()
+                .normalize(),
+            Expr::parse("[{C, D}, {C, E}]").unwrap()
+        );
+        // Inner comprehensions can bind the same variable again.
+        assert_eq!(
+            Expr::parse("[[{x, y} for x in [C]] for x in [A, B], y in [X]]")
+                .unwrap()
+                .normalize(),
+            Expr::parse("{C, X}").unwrap()
+        );
+    }
+
+    #[test]
+    fn resolve_comprehension() {
+        let config = Config::default();
+        let mut env = Env::new(&config);
+        let stmts = Vec::parse("G1 = [A, B] G2 = [B, C] H = {X, Y}").unwrap();
+        stmts.resolve(&mut env).unwrap();
+
+        let expr = Expr::parse("[{x, y, H} for x in G1, y in G2 if x != y]").unwrap();
+        assert_eq!(
+            expr.resolve(&mut env).unwrap().normalize(),
+            Expr::parse("[{A, B, X, Y}, {A, C, X, Y}, {B, C, X, Y}]").unwrap()
+        );
+
+        // Generated nodes aren't looked up again, even when they're bound
+        // later, and variables aren't looked up at all.
+        let config = Config::default().with_shadowing();
+        let mut env = Env::new(&config);
+        let stmts = Vec::parse("G = [A, B] A = {P, Q} x = {P, Q}").unwrap();
+        stmts.resolve(&mut env).unwrap();
+        assert_eq!(
+            Expr::parse("[{x, hub} for x in G]")
+                .unwrap()
+                .resolve(&mut env)
+                .unwrap()
+                .normalize(),
+            Expr::parse("[{A, hub}, {B, hub}]").unwrap()
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Conversions to and from [petgraph](https://docs.rs/petgraph) graphs.
+//!
+//! Expressions convert into [`petgraph::Graph`] and [`StableGraph`], and a
+//! [`Graph`] also converts into a [`GraphMap`], in linear time. Undirected
+//! graphs get a single edge between adjacent nodes, and directed graphs an
+//! edge in each direction.
+//!
+//! ```
+//! use grapl::{Expr, Parse};
+//! use petgraph::graph::UnGraph;
+//!
+//! let expr = Expr::parse("{X, [A, B]}").unwrap();
+//! let graph = UnGraph::from(&expr);
+//! assert_eq!(graph.edge_count(), 2);
+//! assert_eq!(Expr::try_from(&graph).unwrap(), Expr::parse("{[A, B], X}").unwrap());
+//! ```
+//!
+//! A [`Multigraph`] converts into petgraph graphs with a parallel edge for each
+//! repeated edge, and self-loops.
+//!
+//! Going back into an [`Expr`] fails for graphs which expressions can't
+//! describe: those with self-loops, and directed graphs with an edge missing
+//! its reverse. Parallel edges are merged.
+
+use crate::{Expr, Graph, Multigraph, Node};
+use petgraph::EdgeType;
+use petgraph::graph::IndexType;
+use petgraph::graphmap::{GraphMap, NodeTrait};
+use petgraph::stable_graph::StableGraph;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Errors that can occur converting a petgraph graph into an [`Expr`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An edge from a node to itself.
+    SelfLoop(Node),
+    /// A directed edge without an edge back, so it isn't undirected.
+    Asymmetric(Node, Node),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SelfLoop(node) => write!(f, "{} has a self-loop", node),
+            Error::Asymmetric(a, b) => write!(f, "{} -> {} has no reverse edge", a, b),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// The adjacent pairs to add as edges, once for undirected graphs and in both
+// directions for directed ones.
+fn pairs<Ty: EdgeType>(graph: &Graph) -> impl Iterator<Item = (&Node, &Node)> {
+    graph.edges().flat_map(|(a, b)| {
+        let reverse = Ty::is_directed().then_some((b, a));
+        std::iter::once((a, b)).chain(reverse)
+    })
+}
+
+impl<Ty: EdgeType> From<&Graph> for petgraph::Graph<Node, (), Ty> {
+    fn from(graph: &Graph) -> Self {
+        let mut out = petgraph::Graph::with_capacity(graph.node_count(), graph.edge_count());
+        let index: HashMap<_, _> = graph
+            .nodes()
+            .map(|node| (node, out.add_node(node.clone())))
+            .collect();
+        for (a, b) in pairs::<Ty>(graph) {
+            out.add_edge(index[a], index[b], ());
+        }
+        out
+    }
+}
+
+impl<Ty: EdgeType> From<&Expr> for petgraph::Graph<Node, (), Ty> {
+    fn from(expr: &Expr) -> Self {
+        Self::from(&Graph::from(expr))
+    }
+}
+
+impl<Ty: EdgeType> From<&Graph> for StableGraph<Node, (), Ty> {
+    fn from(graph: &Graph) -> Self {
+        let mut out = StableGraph::with_capacity(graph.node_count(), graph.edge_count());
+        let index: HashMap<_, _> = graph
+            .nodes()
+            .map(|node| (node, out.add_node(node.clone())))
+            .collect();
+        for (a, b) in pairs::<Ty>(graph) {
+            out.add_edge(index[a], index[b], ());
+        }
+        out
+    }
+}
+
+// Each edge repeated for its multiplicity, in both directions for directed
+// graphs unless it is a self-loop.
+fn multi_pairs<Ty: EdgeType>(graph: &Multigraph) -> impl Iterator<Item = (&Node, &Node)> {
+    graph.edges().flat_map(|(a, b, n)| {
+        let reverse = (Ty::is_directed() && a != b).then_some((b, a));
+        std::iter::repeat_n(std::iter::once((a, b)).chain(reverse), n).flatten()
+    })
+}
+
+impl<Ty: EdgeType> From<&Multigraph> for petgraph::Graph<Node, (), Ty> {
+    fn from(graph: &Multigraph) -> Self {
+        let mut out = petgraph::Graph::with_capacity(graph.node_count(), graph.edge_count());
+        let index: HashMap<_, _> = graph
+            .nodes()
+            .map(|node| (node, out.add_node(node.clone())))
+            .collect();
+        for (a, b) in multi_pairs::<Ty>(graph) {
+            out.add_edge(index[a], index[b], ());
+        }
+        out
+    }
+}
+
+impl<Ty: EdgeType> From<&Multigraph> for StableGraph<Node, (), Ty> {
+    fn from(graph: &Multigraph) -> Self {
+        let mut out = StableGraph::with_capacity(graph.node_count(), graph.edge_count());
+        let index: HashMap<_, _> = graph
+            .nodes()
+            .map(|node| (node, out.add_node(node.clone())))
+            .collect();
+        for (a, b) in multi_pairs::<Ty>(graph) {
+            out.add_edge(index[a], index[b], ());
+        }
+        out
+    }
+}
+
+impl<Ty: EdgeType> From<&Expr> for StableGraph<Node, (), Ty> {
+    fn from(expr: &Expr) -> Self {
+        Self::from(&Graph::from(expr))
+    }
+}
+
+/// Graph maps need `Copy` nodes, so they borrow the names of the graph's
+/// nodes.
+impl<'g, Ty: EdgeType> From<&'g Graph> for GraphMap<&'g str, (), Ty> {
+    fn from(graph: &'g Graph) -> Self {
+        let mut out = GraphMap::with_capacity(graph.node_count(), graph.edge_count());
+        for node in graph.nodes() {
+            out.add_node(node.as_str());
+        }
+        for (a, b) in pairs::<Ty>(graph) {
+            out.add_edge(a.as_str(), b.as_str(), ());
+        }
+        out
+    }
+}
+
+// Checks that the edges describe an undirected graph without self-loops,
+// and converts it.
+fn try_expr(
+    nodes: impl IntoIterator<Item = Node>,
+    edges: impl IntoIterator<Item = (Node, Node)>,
+    directed: bool,
+) -> Result<Expr, Error> {
+    let mut graph = Graph::new();
+    for node in nodes {
+        graph.add_node(node);
+    }
+    let mut arcs = BTreeSet::new();
+    for (a, b) in edges {
+        if a == b {
+            return Err(Error::SelfLoop(a));
+        }
+        if directed {
+            arcs.insert((a.clone(), b.clone()));
+        }
+        graph.add_edge(a, b);
+    }
+    for (a, b) in &arcs {
+        if !arcs.contains(&(b.clone(), a.clone())) {
+            return Err(Error::Asymmetric(a.clone(), b.clone()));
+        }
+    }
+    Ok(Expr::from(&graph))
+}
+
+impl<E, Ty: EdgeType, Ix: IndexType> TryFrom<&petgraph::Graph<Node, E, Ty, Ix>> for Expr {
+    type Error = Error;
+
+    fn try_from(graph: &petgraph::Graph<Node, E, Ty, Ix>) -> Result<Self, Error> {
+        try_expr(
+            graph.node_weights().cloned(),
+            graph
+                .edge_references()
+                .map(|e| (graph[e.source()].clone(), graph[e.target()].clone())),
+            Ty::is_directed(),
+        )
+    }
+}
+
+impl<E, Ty: EdgeType, Ix: IndexType> TryFrom<&StableGraph<Node, E, Ty, Ix>> for Expr {
+    type Error = Error;
+
+    fn try_from(graph: &StableGraph<Node, E, Ty, Ix>) -> Result<Self, Error> {
+        try_expr(
+            graph.node_weights().cloned(),
+            graph
+                .edge_references()
+                .map(|e| (graph[e.source()].clone(), graph[e.target()].clone())),
+            Ty::is_directed(),
+        )
+    }
+}
+
+impl<N, E, Ty> TryFrom<&GraphMap<N, E, Ty>> for Expr
+where
+    N: NodeTrait + AsRef<str>,
+    Ty: EdgeType,
+{
+    type Error = Error;
+
+    fn try_from(graph: &GraphMap<N, E, Ty>) -> Result<Self, Error> {
+        let node = |n: N| Node::from(n.as_ref());
+        try_expr(
+            graph.nodes().map(node),
+            graph.all_edges().map(|(a, b, _)| (node(a), node(b))),
+            Ty::is_directed(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::{Expr, Graph, Multigraph, Node, Parse};
+    use petgraph::graph::{DiGraph, UnGraph};
+    use petgraph::graphmap::{DiGraphMap, UnGraphMap};
+    use petgraph::stable_graph::{StableDiGraph, StableUnGraph};
+    use pretty_assertions::assert_eq;
+
+    fn expr() -> Expr {
+        Expr::parse("[{X, [A, B]}, {C, D}, E]").unwrap()
+    }
+
+    #[test]
+    fn graph() {
+        let expr = expr();
+        let graph = UnGraph::from(&expr);
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(Expr::try_from(&graph), Ok(Expr::from(&Graph::from(&expr))));
+
+        let graph = DiGraph::from(&expr);
+        assert_eq!(graph.edge_count(), 6);
+        assert_eq!(Expr::try_from(&graph), Ok(Expr::from(&Graph::from(&expr))));
+
+        // The old conversion is still available through `Into`.
+        let graph: DiGraph<Node, ()> = (&expr).into();
+        assert_eq!(graph.edge_count(), 6);
+    }
+
+    #[test]
+    fn stable_graph() {
+        let expr = expr();
+        let mut graph = StableUnGraph::from(&expr);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(Expr::try_from(&graph), Ok(Expr::from(&Graph::from(&expr))));
+
+        // Removing a node keeps the other indices stable.
+        let e = graph.node_indices().find(|i| graph[*i].as_str() == "E");
+        graph.remove_node(e.unwrap());
+        assert_eq!(
+            Expr::try_from(&graph),
+            Ok(Expr::parse("[{[A, B], X}, {C, D}]").unwrap())
+        );
+
+        let graph = StableDiGraph::from(&expr);
+        assert_eq!(graph.edge_count(), 6);
+        assert_eq!(Expr::try_from(&graph), Ok(Expr::from(&Graph::from(&expr))));
+    }
+
+    #[test]
+    fn graph_map() {
+        let graph = Graph::from(&expr());
+        let map = UnGraphMap::from(&graph);
+        assert!(map.contains_edge("A", "X"));
+        assert!(!map.contains_edge("A", "B"));
+        assert_eq!(map.edge_count(), 3);
+        assert_eq!(Expr::try_from(&map), Ok(Expr::from(&graph)));
+
+        let map = DiGraphMap::from(&graph);
+        assert!(map.contains_edge("X", "A"));
+        assert_eq!(map.edge_count(), 6);
+        assert_eq!(Expr::try_from(&map), Ok(Expr::from(&graph)));
+
+        // Product nodes aren't written in the expression, so borrow them
+        // from a graph.
+        let graph = Graph::from(&Expr::parse("{A, B} * {X, Y}").unwrap());
+        let map = UnGraphMap::from(&graph);
+        assert!(map.contains_edge("(A, X)", "(A, Y)"));
+        assert_eq!(map.edge_count(), 4);
+        assert_eq!(Expr::try_from(&map), Ok(Expr::from(&graph)));
+    }
+
+    #[test]
+    fn multigraph() {
+        let graph = Multigraph::from(&Expr::parse("[{A, A}, {A, B}, (A, B, C)]").unwrap());
+        let un = UnGraph::from(&graph);
+        assert_eq!(un.node_count(), 3);
+        assert_eq!(un.edge_count(), 4);
+        let a = un.node_indices().find(|i| un[*i].as_str() == "A").unwrap();
+        let b = un.node_indices().find(|i| un[*i].as_str() == "B").unwrap();
+        assert_eq!(un.edges_connecting(a, b).count(), 2);
+        assert_eq!(un.edges_connecting(a, a).count(), 1);
+
+        // Directed graphs get both directions, except for self-loops.
+        let di = StableDiGraph::from(&graph);
+        assert_eq!(di.edge_count(), 7);
+    }
+
+    #[test]
+    fn try_from_errors() {
+        let mut graph = UnGraph::<Node, ()>::new_undirected();
+        let a = graph.add_node(Node::from("A"));
+        let b = graph.add_node(Node::from("B"));
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, a, ());
+        assert_eq!(Expr::try_from(&graph), Ok(Expr::parse("{A, B}").unwrap()));
+        graph.add_edge(b, b, ());
+        assert_eq!(
+            Expr::try_from(&graph),
+            Err(Error::SelfLoop(Node::from("B")))
+        );
+
+        let graph = DiGraphMap::<&str, ()>::from_edges([("A", "B"), ("B", "A"), ("B", "C")]);
+        assert_eq!(
+            Expr::try_from(&graph),
+            Err(Error::Asymmetric(Node::from("B"), Node::from("C")))
+        );
+    }
+}
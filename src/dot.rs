@@ -0,0 +1,808 @@
+//! Graphviz DOT output.
+//!
+//! With [`Config::with_clusters`] the structure of the expression is kept as
+//! nested `subgraph cluster_*` blocks, and [`Expr::to_dot_in`] also draws the
+//! graphs bound in an [`Env`] as labeled clusters.
+//!
+//! Existing DOT files are read back with [`Expr::from_dot`], which rebuilds an
+//! expression from their edges.
+//!
+//! ```grapl
+//! {X, [A, B]}
+//! => to_dot
+//! graph {
+//!     A;
+//!     B;
+//!     X;
+//!     A -- X;
+//!     B -- X;
+//! }
+//! ```
+
+use crate::limit::{Budget, Limits};
+use crate::resolve::{self, Env};
+use crate::{Expr, Graph, Node, Normalize, is_identifier, normal};
+use chumsky::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// DOT output configuration options.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    name: Option<String>,
+    directed: bool,
+    clusters: bool,
+    graph_attrs: Vec<(String, String)>,
+    node_attrs: Vec<(String, String)>,
+    edge_attrs: Vec<(String, String)>,
+}
+
+impl Config {
+    /// Name the output graph, e.g. `graph G { ... }`.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Output a `digraph` with each edge directed from the smaller node.
+    pub fn with_directed(mut self) -> Self {
+        self.directed = true;
+        self
+    }
+
+    /// Draw each connected and disconnected sub-expression as a cluster.
+    ///
+    /// Graphviz places each node in a single cluster, so a node which appears
+    /// in more than one sub-expression is drawn in the first one.
+    pub fn with_clusters(mut self) -> Self {
+        self.clusters = true;
+        self
+    }
+
+    /// Select the Graphviz layout engine, e.g. `neato` or `circo`.
+    pub fn with_layout(self, layout: impl Into<String>) -> Self {
+        self.with_graph_attr("layout", layout)
+    }
+
+    /// Add a graph attribute, e.g. `rankdir = LR`.
+    pub fn with_graph_attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.graph_attrs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add a default attribute for all nodes, e.g. `shape = circle`.
+    pub fn with_node_attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.node_attrs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add a default attribute for all edges, e.g. `color = gray`.
+    pub fn with_edge_attr(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.edge_attrs.push((key.into(), value.into()));
+        self
+    }
+
+    fn edge_op(&self) -> &'static str {
+        if self.directed { "->" } else { "--" }
+    }
+
+    fn write_header(&self, out: &mut String) {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        match &self.name {
+            Some(name) => writeln!(out, "{} {} {{", kind, id(name)),
+            None => writeln!(out, "{} {{", kind),
+        }
+        .unwrap();
+        for (key, value) in &self.graph_attrs {
+            writeln!(out, "    {} = {};", id(key), id(value)).unwrap();
+        }
+        if !self.node_attrs.is_empty() {
+            writeln!(out, "    node [{}];", attrs(&self.node_attrs)).unwrap();
+        }
+        if !self.edge_attrs.is_empty() {
+            writeln!(out, "    edge [{}];", attrs(&self.edge_attrs)).unwrap();
+        }
+    }
+}
+
+impl Expr {
+    /// Returns the DOT source for this expression's graph.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&Config::default())
+    }
+
+    /// Returns the DOT source for this expression's graph with the given
+    /// configuration.
+    pub fn to_dot_with(&self, config: &Config) -> String {
+        let limits = Limits::default();
+        Writer::new(config, None, &Budget::new(&limits))
+            .write(self)
+            .expect("unlimited budget exceeded")
+    }
+
+    /// Returns the DOT source for this expression's graph, replacing nodes
+    /// bound in the given environment.
+    ///
+    /// With [`Config::with_clusters`] each bound node is drawn as a cluster
+    /// labeled with its name. Comprehensions are expanded and the graph
+    /// normalized within the limits of the environment's configuration.
+    ///
+    /// ```grapl
+    /// G = [A, B]
+    /// {X, G}
+    /// => to_dot_in
+    /// graph {
+    ///     X;
+    ///     subgraph cluster_0 {
+    ///         label = G;
+    ///         style = dashed;
+    ///         A;
+    ///         B;
+    ///     }
+    ///     A -- X;
+    ///     B -- X;
+    /// }
+    /// ```
+    pub fn to_dot_in(&self, env: &Env, config: &Config) -> Result<String, resolve::Error> {
+        Writer::new(config, Some(env), &Budget::new(env.limits())).write(self)
+    }
+}
+
+struct Writer<'a> {
+    config: &'a Config,
+    env: Option<&'a Env<'a>>,
+    budget: &'a Budget<'a>,
+    declared: BTreeSet<Node>,
+    clusters: usize,
+    out: String,
+}
+
+impl<'a> Writer<'a> {
+    fn new(config: &'a Config, env: Option<&'a Env<'a>>, budget: &'a Budget<'a>) -> Self {
+        Writer {
+            config,
+            env,
+            budget,
+            declared: BTreeSet::new(),
+            clusters: 0,
+            out: String::new(),
+        }
+    }
+
+    fn write(mut self, expr: &Expr) -> Result<String, resolve::Error> {
+        self.config.write_header(&mut self.out);
+        let resolved = self.children(expr, true, 0)?;
+
+        let norm = normal::Config::default().with_limits(self.budget.limits().clone());
+        let graph = Graph::from(&resolved.try_normalize_with(&norm)?);
+        for node in graph.nodes() {
+            self.node(node, 0);
+        }
+        for (a, b) in graph.edges() {
+            writeln!(
+                self.out,
+                "    {} {} {};",
+                node_id(a),
+                self.config.edge_op(),
+                node_id(b)
+            )
+            .unwrap();
+        }
+        self.out.push_str("}\n");
+        Ok(self.out)
+    }
+
+    // Writes the nodes of the expression when drawing clusters, returning it
+    // with any bound nodes replaced.
+    fn expr(&mut self, expr: &Expr, lookup: bool, level: usize) -> Result<Expr, resolve::Error> {
+        Ok(match expr {
+            Expr::Node(node) => match self.env.filter(|_| lookup).and_then(|e| e.get(node)) {
+                // Bound expressions are already resolved, so their nodes
+                // refer to nothing.
+                Some(bound) if self.config.clusters => {
+                    self.cluster(bound, false, level, Some(node))?
+                }
+                Some(bound) => self.expr(bound, false, level)?,
+                None => {
+                    if self.config.clusters {
+                        self.node(node, level);
+                    }
+                    expr.clone()
+                }
+            },
+            Expr::Product(_, a, b) | Expr::Subgraph(_, a, b) => {
+                // The nodes of the operands aren't all in the result, so they
+                // are resolved without drawing them.
+                let config = Config::default();
+                let mut quiet = Writer::new(&config, self.env, self.budget);
+                let a = Box::new(quiet.children(a, lookup, level)?);
+                let b = Box::new(quiet.children(b, lookup, level)?);
+                let resolved = match expr {
+                    Expr::Product(product, ..) => Expr::Product(*product, a, b),
+                    Expr::Subgraph(subgraph, ..) => Expr::Subgraph(*subgraph, a, b),
+                    _ => unreachable!(),
+                };
+                if self.config.clusters {
+                    self.cluster(&resolved, false, level, None)?
+                } else {
+                    resolved
+                }
+            }
+            // Comprehensions are resolved as a whole, so generated nodes
+            // aren't looked up, and the sources of generators aren't drawn.
+            Expr::Comprehension(_) => {
+                let config = resolve::Config::default();
+                let empty = Env::new(&config);
+                let env = self.env.filter(|_| lookup).unwrap_or(&empty);
+                let expanded = expr.resolve_within(env, self.budget, 0)?.0;
+                self.expr(&expanded, false, level)?
+            }
+            _ if self.config.clusters => self.cluster(expr, lookup, level, None)?,
+            _ => self.children(expr, lookup, level)?,
+        })
+    }
+
+    fn children(
+        &mut self,
+        expr: &Expr,
+        lookup: bool,
+        level: usize,
+    ) -> Result<Expr, resolve::Error> {
+        let mut all = |exprs: &[Expr]| -> Result<Vec<_>, resolve::Error> {
+            exprs.iter().map(|e| self.expr(e, lookup, level)).collect()
+        };
+        Ok(match expr {
+            Expr::Connected(exprs) => Expr::Connected(all(exprs)?),
+            Expr::Disconnected(exprs) => Expr::Disconnected(all(exprs)?),
+            Expr::Path(exprs) => Expr::Path(all(exprs)?),
+            Expr::Cycle(exprs) => Expr::Cycle(all(exprs)?),
+            Expr::Node(_) | Expr::Product(..) | Expr::Subgraph(..) | Expr::Comprehension(_) => {
+                self.expr(expr, lookup, level)?
+            }
+        })
+    }
+
+    fn cluster(
+        &mut self,
+        expr: &Expr,
+        lookup: bool,
+        level: usize,
+        label: Option<&Node>,
+    ) -> Result<Expr, resolve::Error> {
+        writeln!(
+            self.out,
+            "{}subgraph cluster_{} {{",
+            indent(level),
+            self.clusters
+        )
+        .unwrap();
+        self.clusters += 1;
+        if let Some(label) = label {
+            writeln!(self.out, "{}label = {};", indent(level + 1), node_id(label)).unwrap();
+        }
+        if let Expr::Disconnected(_) = expr {
+            writeln!(self.out, "{}style = dashed;", indent(level + 1)).unwrap();
+        }
+        let resolved = match expr {
+            Expr::Node(node) => {
+                self.node(node, level + 1);
+                expr.clone()
+            }
+            Expr::Product(..) | Expr::Subgraph(..) => {
+                for node in expr.nodes_within(self.budget)? {
+                    self.node(&node, level + 1);
+                }
+                expr.clone()
+            }
+            _ => self.children(expr, lookup, level + 1)?,
+        };
+        writeln!(self.out, "{}}}", indent(level)).unwrap();
+        Ok(resolved)
+    }
+
+    fn node(&mut self, node: &Node, level: usize) {
+        if self.declared.insert(node.clone()) {
+            writeln!(self.out, "{}{};", indent(level), node_id(node)).unwrap();
+        }
+    }
+}
+
+fn indent(level: usize) -> String {
+    "    ".repeat(level + 1)
+}
+
+fn attrs(attrs: &[(String, String)]) -> String {
+    attrs
+        .iter()
+        .map(|(k, v)| format!("{} = {}", id(k), id(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn node_id(node: &Node) -> String {
+    id(node.as_str())
+}
+
+// DOT keywords, which are case-insensitive.
+const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+// DOT identifiers are quoted unless they're plain alphanumeric names which
+// aren't keywords.
+fn id(s: &str) -> String {
+    let plain = is_identifier(s) && !KEYWORDS.iter().any(|k| s.eq_ignore_ascii_case(k));
+    if plain {
+        s.to_string()
+    } else {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+impl Expr {
+    /// Parses DOT source into an expression of its graph.
+    ///
+    /// See [`read`] for how the source is interpreted.
+    pub fn from_dot(input: &str) -> Result<Expr, Vec<EmptyErr>> {
+        read(input).map(|graph| Expr::from(&graph))
+    }
+}
+
+/// Parses DOT source into the graph of its nodes and edges.
+///
+/// Nodes are named by their `label` attribute when they have one, and their
+/// id otherwise. Edge direction, ports and all other attributes are ignored,
+/// and edges to a subgraph connect to each of its nodes.
+///
+/// ```
+/// use grapl::{Expr, Parse, dot};
+///
+/// let graph = dot::read("digraph { 0 [label = A]; 1 [label = B]; 0 -> 1 }").unwrap();
+/// assert_eq!(Expr::from(&graph), Expr::parse("{A, B}").unwrap());
+/// ```
+pub fn read(input: &str) -> Result<Graph, Vec<EmptyErr>> {
+    let stmts = parser().parse(input).into_result()?;
+    let mut reader = Reader::default();
+    reader.stmts(&stmts);
+
+    let name = |id: &String| Node::from(reader.labels.get(id).unwrap_or(id).as_str());
+    let mut graph = Graph::new();
+    for id in &reader.nodes {
+        graph.add_node(name(id));
+    }
+    for (a, b) in &reader.edges {
+        graph.add_edge(name(a), name(b));
+    }
+    Ok(graph)
+}
+
+#[derive(Clone, Debug)]
+enum Stmt {
+    Node(String, Vec<(String, String)>),
+    Edge(Vec<Operand>),
+    Subgraph(Vec<Stmt>),
+    Ignored,
+}
+
+#[derive(Clone, Debug)]
+enum Operand {
+    Node(String),
+    Subgraph(Vec<Stmt>),
+}
+
+#[derive(Default)]
+struct Reader {
+    labels: HashMap<String, String>,
+    nodes: Vec<String>,
+    edges: Vec<(String, String)>,
+}
+
+impl Reader {
+    // Reads the statements, returning the ids of the nodes they mention.
+    fn stmts(&mut self, stmts: &[Stmt]) -> Vec<String> {
+        let mut ids = vec![];
+        for stmt in stmts {
+            match stmt {
+                Stmt::Node(id, attrs) => {
+                    if let Some((_, label)) = attrs.iter().rev().find(|(k, _)| k == "label") {
+                        self.labels.insert(id.clone(), label.clone());
+                    }
+                    ids.push(self.node(id));
+                }
+                Stmt::Edge(operands) => {
+                    let operands: Vec<_> = operands
+                        .iter()
+                        .map(|operand| match operand {
+                            Operand::Node(id) => vec![self.node(id)],
+                            Operand::Subgraph(stmts) => self.stmts(stmts),
+                        })
+                        .collect();
+                    for pair in operands.windows(2) {
+                        for a in &pair[0] {
+                            for b in &pair[1] {
+                                self.edges.push((a.clone(), b.clone()));
+                            }
+                        }
+                    }
+                    ids.extend(operands.into_iter().flatten());
+                }
+                Stmt::Subgraph(stmts) => ids.extend(self.stmts(stmts)),
+                Stmt::Ignored => {}
+            }
+        }
+        ids
+    }
+
+    fn node(&mut self, id: &str) -> String {
+        self.nodes.push(id.to_string());
+        id.to_string()
+    }
+}
+
+fn parser<'src>() -> impl Parser<'src, &'src str, Vec<Stmt>> {
+    let line = any().and_is(text::newline().not()).repeated();
+    let comment = choice((
+        just("//").then(line).ignored(),
+        just('#').then(line).ignored(),
+        just("/*")
+            .then(any().and_is(just("*/").not()).repeated())
+            .then(just("*/"))
+            .ignored(),
+    ));
+    let pad = choice((text::whitespace().at_least(1), comment))
+        .repeated()
+        .ignored()
+        .boxed();
+    let token = |c| just(c).padded_by(pad.clone());
+
+    // Keywords are case-insensitive.
+    let keyword = |word: &'static str| {
+        text::ascii::ident()
+            .try_map(move |s: &str, _| {
+                if s.eq_ignore_ascii_case(word) {
+                    Ok(())
+                } else {
+                    Err(EmptyErr::default())
+                }
+            })
+            .padded_by(pad.clone())
+    };
+
+    let numeral = just('-')
+        .or_not()
+        .then(choice((
+            text::digits(10)
+                .then(just('.').then(text::digits(10).or_not()).or_not())
+                .ignored(),
+            just('.').then(text::digits(10)).ignored(),
+        )))
+        .to_slice();
+    let quoted = choice((just("\\\"").to('"'), none_of('"')))
+        .repeated()
+        .collect::<String>()
+        .delimited_by(just('"'), just('"'));
+    let id = choice((
+        text::ascii::ident().map(str::to_string),
+        numeral.map(str::to_string),
+        quoted,
+    ))
+    .padded_by(pad.clone())
+    .boxed();
+
+    let attr = id
+        .clone()
+        .then_ignore(token('='))
+        .then(id.clone())
+        .then_ignore(token(',').or(token(';')).or_not());
+    let attrs = attr
+        .repeated()
+        .collect::<Vec<_>>()
+        .delimited_by(token('['), token(']'))
+        .repeated()
+        .at_least(1)
+        .collect::<Vec<_>>()
+        .map(|lists| lists.concat())
+        .boxed();
+
+    let port = token(':').then(id.clone()).repeated().at_most(2);
+    let node_id = id.clone().then_ignore(port).boxed();
+
+    let stmts = recursive(|stmts| {
+        let subgraph = keyword("subgraph")
+            .then(id.clone().or_not())
+            .or_not()
+            .ignore_then(stmts.delimited_by(token('{'), token('}')))
+            .boxed();
+
+        let operand = choice((
+            subgraph.clone().map(Operand::Subgraph),
+            node_id.clone().map(Operand::Node),
+        ))
+        .boxed();
+        let edge_op = just("--").or(just("->")).padded_by(pad.clone());
+        let edge = operand
+            .clone()
+            .then(
+                edge_op
+                    .ignore_then(operand)
+                    .repeated()
+                    .at_least(1)
+                    .collect::<Vec<_>>(),
+            )
+            .then_ignore(attrs.clone().or_not())
+            .map(|(first, rest)| Stmt::Edge(std::iter::once(first).chain(rest).collect()))
+            .boxed();
+
+        let attr_stmt = choice((keyword("graph"), keyword("node"), keyword("edge")))
+            .then(attrs.clone())
+            .to(Stmt::Ignored)
+            .boxed();
+        let assign = id
+            .clone()
+            .then(token('='))
+            .then(id.clone())
+            .to(Stmt::Ignored)
+            .boxed();
+        let node = node_id
+            .clone()
+            .then(attrs.clone().or_not())
+            .map(|(id, attrs)| Stmt::Node(id, attrs.unwrap_or_default()))
+            .boxed();
+
+        choice((attr_stmt, edge, subgraph.map(Stmt::Subgraph), assign, node))
+            .then_ignore(token(';').or_not())
+            .repeated()
+            .collect::<Vec<_>>()
+            .boxed()
+    });
+
+    keyword("strict")
+        .or_not()
+        .then(keyword("graph").or(keyword("digraph")))
+        .then(id.or_not())
+        .ignore_then(stmts.delimited_by(token('{'), token('}')))
+        .padded_by(pad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::limit::{Limit, Limits};
+    use crate::resolve::{self, Env};
+    use crate::{Expr, Graph, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_dot() {
+        assert_eq!(Expr::parse("[]").unwrap().to_dot(), "graph {\n}\n");
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_dot(),
+            "graph {
+    A;
+    B;
+    X;
+    A -- X;
+    B -- X;
+}
+"
+        );
+    }
+
+    #[test]
+    fn to_dot_with() {
+        let config = Config::default()
+            .with_name("G")
+            .with_directed()
+            .with_layout("circo")
+            .with_graph_attr("label", "Star graph")
+            .with_node_attr("shape", "circle")
+            .with_edge_attr("color", "gray");
+        assert_eq!(
+            Expr::parse("{A, [B, C]}").unwrap().to_dot_with(&config),
+            r#"digraph G {
+    layout = circo;
+    label = "Star graph";
+    node [shape = circle];
+    edge [color = gray];
+    A;
+    B;
+    C;
+    A -> B;
+    A -> C;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn to_dot_keywords() {
+        assert_eq!(
+            Expr::parse("{node, [Edge, STRICT, graphs]}")
+                .unwrap()
+                .to_dot(),
+            r#"graph {
+    "Edge";
+    "STRICT";
+    graphs;
+    "node";
+    "Edge" -- "node";
+    "STRICT" -- "node";
+    graphs -- "node";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn to_dot_clusters() {
+        let config = Config::default().with_clusters();
+        assert_eq!(
+            Expr::parse("[{A, B, C}, {C, D}]")
+                .unwrap()
+                .to_dot_with(&config),
+            "graph {
+    subgraph cluster_0 {
+        A;
+        B;
+        C;
+    }
+    subgraph cluster_1 {
+        D;
+    }
+    A -- B;
+    A -- C;
+    B -- C;
+    C -- D;
+}
+"
+        );
+        assert_eq!(
+            Expr::parse("{X, [A, {B, C}]}")
+                .unwrap()
+                .to_dot_with(&config),
+            "graph {
+    X;
+    subgraph cluster_0 {
+        style = dashed;
+        A;
+        subgraph cluster_1 {
+            B;
+            C;
+        }
+    }
+    A -- X;
+    B -- C;
+    B -- X;
+    C -- X;
+}
+"
+        );
+    }
+
+    #[test]
+    fn to_dot_in() {
+        let config = resolve::Config::default();
+        let mut env = Env::new(&config);
+        env.insert(Node::from("G"), Expr::parse("[A, B]").unwrap())
+            .unwrap();
+        let expr = Expr::parse("{X, G}").unwrap();
+        assert_eq!(
+            expr.to_dot_in(&env, &Config::default()).unwrap(),
+            Expr::parse("{X, [A, B]}").unwrap().to_dot()
+        );
+        assert_eq!(
+            expr.to_dot_in(&env, &Config::default().with_clusters())
+                .unwrap(),
+            "graph {
+    X;
+    subgraph cluster_0 {
+        label = G;
+        style = dashed;
+        A;
+        B;
+    }
+    A -- X;
+    B -- X;
+}
+"
+        );
+
+        // Products only draw their own nodes.
+        let expr = Expr::parse("G * {X, Y}").unwrap();
+        assert_eq!(
+            expr.to_dot_in(&env, &Config::default().with_clusters())
+                .unwrap(),
+            r#"graph {
+    subgraph cluster_0 {
+        "(A, X)";
+        "(A, Y)";
+        "(B, X)";
+        "(B, Y)";
+    }
+    "(A, X)" -- "(A, Y)";
+    "(B, X)" -- "(B, Y)";
+}
+"#
+        );
+
+        // Comprehensions draw the nodes they generate, and are expanded
+        // within the environment's limits.
+        let expr = Expr::parse("[{x, hub} for x in G]").unwrap();
+        assert_eq!(
+            expr.to_dot_in(&env, &Config::default()).unwrap(),
+            Expr::parse("{hub, [A, B]}").unwrap().to_dot()
+        );
+        let config = resolve::Config::default().with_limits(Limits::default().with_nodes(3));
+        let env = Env::from_bindings(&config, [(Node::from("G"), Expr::parse("[A, B]").unwrap())])
+            .unwrap();
+        assert_eq!(
+            expr.to_dot_in(&env, &Config::default()),
+            Err(resolve::Error::LimitExceeded(Limit::Nodes))
+        );
+    }
+
+    #[test]
+    fn from_dot() {
+        let from_dot = |s| Expr::from_dot(s).unwrap();
+        assert_eq!(from_dot("graph {}"), Expr::parse("[]").unwrap());
+        assert_eq!(
+            from_dot("strict Graph G { A -- B -- C; A -- C; D }"),
+            Expr::parse("[{A, B, C}, D]").unwrap()
+        );
+        assert_eq!(
+            from_dot(
+                r#"
+                /* Each subgraph node is connected to X. */
+                digraph "star" {
+                    rankdir = LR; // left to right
+                    node [shape = circle, color = "gray"]
+                    # Ports are ignored.
+                    X:n -> { A B "C D" } [color = red]
+                    subgraph cluster_0 { label = "E"; E }
+                }
+                "#
+            ),
+            Expr::from(
+                &[("X", "A"), ("X", "B"), ("X", "C D"), ("E", "E")]
+                    .into_iter()
+                    .map(|(a, b)| (Node::from(a), Node::from(b)))
+                    .collect::<Graph>()
+            )
+        );
+        assert!(Expr::from_dot("graph { A -- }").is_err());
+        assert!(Expr::from_dot("A -- B").is_err());
+    }
+
+    #[test]
+    fn from_dot_round_trip() {
+        for s in [
+            "A",
+            "{X, [A, B]}",
+            "[{A, B}, {B, C}, {C, A}, D]",
+            "{subgraph, [digraph, Node]}",
+        ] {
+            let expr = Expr::parse(s).unwrap();
+            let config = Config::default().with_directed().with_clusters();
+            for dot in [expr.to_dot(), expr.to_dot_with(&config)] {
+                let read = Expr::from_dot(&dot).unwrap();
+                assert_eq!(read.nodes(), expr.nodes());
+                assert_eq!(read.edges(), expr.edges());
+            }
+        }
+    }
+
+    #[test]
+    fn from_petgraph_dot() {
+        // What `!viz` wrote when it used petgraph's DOT output.
+        let dot = r#"graph {
+    0 [ label = "X"]
+    1 [ label = "A"]
+    2 [ label = "B"]
+    0 -- 1 [ ]
+    0 -- 2 [ ]
+}
+"#;
+        assert_eq!(
+            Expr::from_dot(dot).unwrap().edges(),
+            Expr::parse("{X, [A, B]}").unwrap().edges()
+        );
+    }
+}
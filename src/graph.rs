@@ -0,0 +1,420 @@
+//! Graphs as sets of nodes and their adjacent nodes.
+//!
+//! A [`Graph`] is the plain node and edge set described by an [`Expr`]. It can
+//! be queried directly without walking the expression, and converted back into
+//! an [`Expr`] which reconstructs connected and disconnected groups from the
+//! edges alone.
+//!
+//! With the `serde` feature a graph serializes as a flat list of its nodes and
+//! edges, e.g. `{"nodes": ["A", "B", "X"], "edges": [["A", "X"], ["B",
+//! "X"]]}`.
+
+use crate::{Expr, Node};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An undirected graph without self-loops, stored as adjacency sets. See
+/// [`Multigraph`](crate::Multigraph) to keep self-loops and parallel edges.
+///
+/// ```
+/// use grapl::{Expr, Graph, Node, Parse};
+///
+/// let graph = Graph::from(&Expr::parse("{X, [A, B]}").unwrap());
+/// assert!(graph.has_edge(&Node::from("X"), &Node::from("A")));
+/// assert!(!graph.has_edge(&Node::from("A"), &Node::from("B")));
+/// assert_eq!(graph.degree(&Node::from("X")), 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Flat"))]
+pub struct Graph(BTreeMap<Node, BTreeSet<Node>>);
+
+impl Graph {
+    /// Create a new graph without any nodes.
+    pub fn new() -> Self {
+        Graph(BTreeMap::new())
+    }
+
+    /// Adds a node without any edges, if it isn't already in this graph.
+    pub fn add_node(&mut self, node: Node) {
+        self.0.entry(node).or_default();
+    }
+
+    /// Adds an edge between two nodes, adding the nodes as needed.
+    ///
+    /// Self-loops are ignored, just like in [`Expr::edges`].
+    pub fn add_edge(&mut self, a: Node, b: Node) {
+        if a == b {
+            self.add_node(a);
+        } else {
+            self.0.entry(a.clone()).or_default().insert(b.clone());
+            self.0.entry(b).or_default().insert(a);
+        }
+    }
+
+    /// Removes the edge between two nodes, keeping both nodes. Returns true
+    /// if the edge was in this graph.
+    pub fn remove_edge(&mut self, a: &Node, b: &Node) -> bool {
+        let removed = self.0.get_mut(a).is_some_and(|adjacent| adjacent.remove(b));
+        if let Some(adjacent) = self.0.get_mut(b) {
+            adjacent.remove(a);
+        }
+        removed
+    }
+
+    /// Returns true if the given node is in this graph.
+    pub fn has_node(&self, node: &Node) -> bool {
+        self.0.contains_key(node)
+    }
+
+    /// Returns true if there is an edge between the given nodes.
+    pub fn has_edge(&self, a: &Node, b: &Node) -> bool {
+        self.0.get(a).is_some_and(|adjacent| adjacent.contains(b))
+    }
+
+    /// Returns the **sorted** nodes adjacent to the given node.
+    pub fn neighbors(&self, node: &Node) -> impl Iterator<Item = &Node> {
+        self.0.get(node).into_iter().flatten()
+    }
+
+    /// Returns the number of edges of the given node.
+    pub fn degree(&self, node: &Node) -> usize {
+        self.0.get(node).map_or(0, BTreeSet::len)
+    }
+
+    /// Returns the **sorted** nodes of this graph.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.0.keys()
+    }
+
+    /// Returns the **sorted** edges of this graph, with each undirected edge
+    /// only once as `(a, b)` where `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (&Node, &Node)> {
+        self.0
+            .iter()
+            .flat_map(|(a, adjacent)| adjacent.range::<Node, _>(a..).map(move |b| (a, b)))
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.0.values().map(BTreeSet::len).sum::<usize>() / 2
+    }
+
+    /// Returns the graph with the same nodes and exactly the edges which are
+    /// missing from this graph.
+    pub fn complement(&self) -> Graph {
+        let mut complement = Graph::new();
+        for (a, adjacent) in &self.0 {
+            complement.add_node(a.clone());
+            for b in self.0.keys().filter(|b| *b != a && !adjacent.contains(*b)) {
+                complement.add_edge(a.clone(), b.clone());
+            }
+        }
+        complement
+    }
+
+    /// Returns the subgraph induced by the given nodes.
+    pub fn subgraph(&self, nodes: &BTreeSet<Node>) -> Graph {
+        Graph(
+            self.0
+                .iter()
+                .filter(|(node, _)| nodes.contains(*node))
+                .map(|(node, adjacent)| (node.clone(), adjacent & nodes))
+                .collect(),
+        )
+    }
+
+    /// Returns the node sets of each connected component of this graph,
+    /// ordered by their smallest node.
+    pub fn components(&self) -> Vec<BTreeSet<Node>> {
+        let mut seen = BTreeSet::new();
+        let mut components = vec![];
+        for node in self.0.keys() {
+            if seen.contains(node) {
+                continue;
+            }
+            let mut component = BTreeSet::new();
+            let mut stack = vec![node];
+            while let Some(node) = stack.pop() {
+                if component.insert(node.clone()) {
+                    stack.extend(self.neighbors(node).filter(|n| !component.contains(*n)));
+                }
+            }
+            seen.extend(component.iter().cloned());
+            components.push(component);
+        }
+        components
+    }
+}
+
+impl From<&Expr> for Graph {
+    fn from(expr: &Expr) -> Self {
+        let mut graph = Graph::new();
+        for node in expr.nodes() {
+            graph.add_node(node);
+        }
+        for (a, b) in expr.edges() {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+}
+
+impl FromIterator<(Node, Node)> for Graph {
+    fn from_iter<I: IntoIterator<Item = (Node, Node)>>(edges: I) -> Self {
+        let mut graph = Graph::new();
+        graph.extend(edges);
+        graph
+    }
+}
+
+impl Extend<(Node, Node)> for Graph {
+    fn extend<I: IntoIterator<Item = (Node, Node)>>(&mut self, edges: I) {
+        for (a, b) in edges {
+            self.add_edge(a, b);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Graph {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        struct Edges<'g>(&'g Graph);
+
+        impl serde::Serialize for Edges<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.edges())
+            }
+        }
+
+        let mut flat = serializer.serialize_struct("Graph", 2)?;
+        flat.serialize_field("nodes", &self.0.keys().collect::<Vec<_>>())?;
+        flat.serialize_field("edges", &Edges(self))?;
+        flat.end()
+    }
+}
+
+// The serialized form of a graph. Nodes only need to be listed when they don't
+// have any edges.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Flat {
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    edges: Vec<(Node, Node)>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Flat> for Graph {
+    fn from(flat: Flat) -> Self {
+        let mut graph: Graph = flat.edges.into_iter().collect();
+        for node in flat.nodes {
+            graph.add_node(node);
+        }
+        graph
+    }
+}
+
+/// Rebuilds an expression from the graph's edges.
+///
+/// Disconnected components become disconnected expressions and components
+/// which are fully connected to each other become connected expressions. Any
+/// part of the graph which can't be split this way is covered by cliques.
+///
+/// ```grapl
+/// A-C, A-D, B-C, B-D
+/// => {[A, B], [C, D]}
+/// ```
+impl From<&Graph> for Expr {
+    fn from(graph: &Graph) -> Self {
+        let mut nodes = graph.nodes();
+        match (nodes.next(), nodes.next()) {
+            (None, _) => return Expr::Disconnected(vec![]),
+            (Some(node), None) => return Expr::Node(node.clone()),
+            _ => {}
+        }
+
+        let components = graph.components();
+        if components.len() > 1 {
+            let mut ds = vec![];
+            for component in &components {
+                match Expr::from(&graph.subgraph(component)) {
+                    Expr::Disconnected(exprs) => ds.extend(exprs),
+                    e => ds.push(e),
+                }
+            }
+            return Expr::Disconnected(ds);
+        }
+
+        let co_components = graph.complement().components();
+        if co_components.len() > 1 {
+            let cs = co_components
+                .iter()
+                .map(|component| Expr::from(&graph.subgraph(component)))
+                .collect();
+            return Expr::Connected(cs);
+        }
+
+        Expr::Disconnected(clique_cover(graph))
+    }
+}
+
+// An id like `n{i}` for the node at the given index, for formats which can't
+// use its name. Underscores are added until it isn't the name of any node, and
+// the digits keep it from clashing with the alias of another index.
+pub(crate) fn alias(graph: &Graph, prefix: char, i: usize) -> String {
+    let mut id = format!("{}{}", prefix, i);
+    while graph.has_node(&Node::from(id.as_str())) {
+        id.push('_');
+    }
+    id
+}
+
+// Covers every edge with a clique as in `clique_cover`, and every node without
+// edges with itself.
+pub(crate) fn cliques(graph: &Graph) -> Vec<Expr> {
+    let mut cliques = clique_cover(graph);
+    cliques.extend(
+        graph
+            .nodes()
+            .filter(|n| graph.degree(n) == 0)
+            .map(|n| Expr::Node(n.clone())),
+    );
+    cliques
+}
+
+// Greedily cover every edge with a clique, growing each clique from the first
+// uncovered edge in order.
+pub(crate) fn clique_cover(graph: &Graph) -> Vec<Expr> {
+    let mut covered: BTreeSet<(&Node, &Node)> = BTreeSet::new();
+    let mut cliques = vec![];
+    for (a, b) in graph.edges() {
+        if covered.contains(&(a, b)) {
+            continue;
+        }
+        let mut clique = vec![a, b];
+        for c in graph.neighbors(a) {
+            if !clique.contains(&c) && clique.iter().all(|n| graph.has_edge(n, c)) {
+                clique.push(c);
+            }
+        }
+        clique.sort();
+        for (i, x) in clique.iter().enumerate() {
+            for y in &clique[i + 1..] {
+                covered.insert((*x, *y));
+            }
+        }
+        cliques.push(Expr::Connected(
+            clique.into_iter().cloned().map(Expr::Node).collect(),
+        ));
+    }
+    cliques
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+    use crate::{Expr, Node, Normalize, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn graph_queries() {
+        let graph = Graph::from(&Expr::parse("[{A, [B, C]}, D]").unwrap());
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.has_node(&Node::from("D")));
+        assert!(!graph.has_node(&Node::from("E")));
+        assert!(graph.has_edge(&Node::from("A"), &Node::from("B")));
+        assert!(graph.has_edge(&Node::from("B"), &Node::from("A")));
+        assert!(!graph.has_edge(&Node::from("B"), &Node::from("C")));
+        assert_eq!(
+            graph.neighbors(&Node::from("A")).collect::<Vec<_>>(),
+            vec![&Node::from("B"), &Node::from("C")]
+        );
+        assert_eq!(graph.degree(&Node::from("A")), 2);
+        assert_eq!(graph.degree(&Node::from("D")), 0);
+        assert_eq!(graph.degree(&Node::from("E")), 0);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![
+                (&Node::from("A"), &Node::from("B")),
+                (&Node::from("A"), &Node::from("C"))
+            ]
+        );
+
+        let mut graph = graph;
+        assert!(graph.remove_edge(&Node::from("B"), &Node::from("A")));
+        assert!(!graph.remove_edge(&Node::from("B"), &Node::from("A")));
+        assert!(!graph.remove_edge(&Node::from("A"), &Node::from("E")));
+        assert!(!graph.has_edge(&Node::from("A"), &Node::from("B")));
+        assert!(graph.has_node(&Node::from("B")));
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn graph_components() {
+        let graph = Graph::from(&Expr::parse("[{A, B}, {B, C}, D, {E, F}]").unwrap());
+        assert_eq!(
+            graph.components(),
+            vec![
+                [Node::from("A"), Node::from("B"), Node::from("C")].into(),
+                [Node::from("D")].into(),
+                [Node::from("E"), Node::from("F")].into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn graph_to_expr() {
+        let to_expr = |s| Expr::from(&Graph::from(&Expr::parse(s).unwrap()));
+        assert_eq!(to_expr("[]"), Expr::parse("[]").unwrap());
+        assert_eq!(to_expr("{A}"), Expr::parse("A").unwrap());
+        assert_eq!(to_expr("[A, B]"), Expr::parse("[A, B]").unwrap());
+        assert_eq!(to_expr("{A, B, C}"), Expr::parse("{A, B, C}").unwrap());
+        assert_eq!(
+            to_expr("[{A, C}, {A, D}, {B, C}, {B, D}]"),
+            Expr::parse("{[A, B], [C, D]}").unwrap()
+        );
+        assert_eq!(
+            to_expr("[{A, B}, {B, C}, {C, D}, E]"),
+            Expr::parse("[{A, B}, {B, C}, {C, D}, E]").unwrap()
+        );
+    }
+
+    #[test]
+    fn graph_expr_round_trip() {
+        for s in [
+            "{A, [B, C], D}",
+            "[{A, B}, {B, C}, {C, A}, {C, D}]",
+            "{[A, {B, [C, D]}], [E, F]}",
+            "[{A, B, C}, {C, D, E}, {E, F, A}]",
+        ] {
+            let expr = Expr::parse(s).unwrap();
+            let rebuilt = Expr::from(&Graph::from(&expr));
+            assert_eq!(rebuilt.nodes(), expr.nodes());
+            assert_eq!(rebuilt.edges(), expr.edges());
+            assert_eq!(rebuilt.normalize().edges(), expr.edges());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn graph_serde() {
+        let graph = Graph::from(&Expr::parse("{X, [A, B]}").unwrap());
+        let json = serde_json::to_string(&graph).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nodes":["A","B","X"],"edges":[["A","X"],["B","X"]]}"#
+        );
+        assert_eq!(serde_json::from_str::<Graph>(&json).unwrap(), graph);
+        assert_eq!(
+            serde_json::from_str::<Graph>(r#"{"edges":[["X","A"],["X","B"]]}"#).unwrap(),
+            graph
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! GraphML input and output, for tools like yEd and Gephi.
+//!
+//! GraphML only describes nodes and edges, so reading a document rebuilds the
+//! expression from its edges with [`Graph`](crate::Graph). Node attributes are
+//! kept separately as [`Attrs`], and any other GraphML data is ignored.
+//!
+//! ```
+//! use grapl::graphml::{self, Attrs};
+//! use grapl::{Expr, Graph, Node, Parse};
+//!
+//! let expr = Expr::parse("{X, [A, B]}").unwrap();
+//! let mut attrs = Attrs::new();
+//! attrs
+//!     .entry(Node::from("X"))
+//!     .or_default()
+//!     .insert("color".into(), "red".into());
+//!
+//! let xml = expr.to_graphml_with(&attrs);
+//! let (graph, read) = graphml::read(&xml).unwrap();
+//! assert_eq!(graph, Graph::from(&expr));
+//! assert_eq!(read, attrs);
+//! ```
+
+use crate::Node;
+use std::collections::BTreeMap;
+use std::fmt;
+
+mod reader;
+mod writer;
+
+pub use self::reader::read;
+pub use self::writer::write;
+
+/// Attributes of each node by name, e.g. `color` or `weight`.
+pub type Attrs = BTreeMap<Node, BTreeMap<String, String>>;
+
+/// Errors that can occur reading GraphML.
+#[derive(Debug)]
+pub enum Error {
+    /// The input isn't valid XML.
+    Xml(quick_xml::Error),
+    /// An element is missing a required attribute, e.g. a node's `id`.
+    MissingAttribute(&'static str, &'static str),
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(err: quick_xml::Error) -> Self {
+        Error::Xml(err)
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for Error {
+    fn from(err: quick_xml::events::attributes::AttrError) -> Self {
+        Error::Xml(err.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Xml(err) => write!(f, "invalid XML: {}", err),
+            Error::MissingAttribute(element, attr) => {
+                write!(f, "<{}> is missing the {} attribute", element, attr)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,143 @@
+use super::{Attrs, Error};
+use crate::{Expr, Graph, Node};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+impl Expr {
+    /// Parses a GraphML document into an expression of its graph.
+    ///
+    /// See [`read`] for the node attributes as well.
+    pub fn from_graphml(xml: &str) -> Result<Expr, Error> {
+        read(xml).map(|(graph, _)| Expr::from(&graph))
+    }
+}
+
+/// Parses a GraphML document into its graph and node attributes.
+///
+/// Node ids are used as node names, and `<data>` values are named by their
+/// `<key>`'s `attr.name` when it has one. Edge direction is ignored.
+pub fn read(xml: &str) -> Result<(Graph, Attrs), Error> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut graph = Graph::new();
+    let mut attrs = Attrs::new();
+    let mut keys = HashMap::new();
+    // The node and data key currently being read.
+    let mut node = None;
+    let mut data = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"key" => {
+                let id = attribute(&e, "key", "id")?;
+                let name = e
+                    .try_get_attribute("attr.name")?
+                    .map(|a| a.unescape_value().map(|v| v.into_owned()))
+                    .transpose()?
+                    .unwrap_or_else(|| id.clone());
+                keys.insert(id, name);
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"node" => {
+                node = Some(Node::from(attribute(&e, "node", "id")?));
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"node" => {
+                graph.add_node(Node::from(attribute(&e, "node", "id")?));
+            }
+            Event::End(e) if e.local_name().as_ref() == b"node" => {
+                if let Some(node) = node.take() {
+                    graph.add_node(node);
+                }
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"data" => {
+                data = Some(attribute(&e, "data", "key")?);
+            }
+            Event::End(e) if e.local_name().as_ref() == b"data" => {
+                data = None;
+            }
+            Event::Text(text) => {
+                if let (Some(node), Some(key)) = (&node, &data) {
+                    let name = keys.get(key).unwrap_or(key);
+                    attrs
+                        .entry(node.clone())
+                        .or_default()
+                        .insert(name.clone(), text.unescape()?.into_owned());
+                }
+            }
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"edge" => {
+                let source = attribute(&e, "edge", "source")?;
+                let target = attribute(&e, "edge", "target")?;
+                graph.add_edge(Node::from(source), Node::from(target));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((graph, attrs))
+}
+
+fn attribute(e: &BytesStart, element: &'static str, name: &'static str) -> Result<String, Error> {
+    match e.try_get_attribute(name)? {
+        Some(attr) => Ok(attr.unescape_value()?.into_owned()),
+        None => Err(Error::MissingAttribute(element, name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphml::{self, Error};
+    use crate::{Expr, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_graphml() {
+        // Roughly what Gephi exports, with a directed edge and edge data.
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key attr.name="label" attr.type="string" for="node" id="label"/>
+  <key attr.name="weight" attr.type="double" for="edge" id="weight"/>
+  <graph edgedefault="directed">
+    <node id="n0"><data key="label">Hub &amp; spoke</data></node>
+    <node id="n1"/>
+    <node id="n2"></node>
+    <node id="n3"/>
+    <edge source="n0" target="n1"><data key="weight">1.0</data></edge>
+    <edge source="n2" target="n0"/>
+  </graph>
+</graphml>
+"#;
+        let (graph, attrs) = graphml::read(xml).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(attrs[&Node::from("n0")]["label"], "Hub & spoke".to_string());
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(
+            Expr::from_graphml(xml).unwrap(),
+            Expr::parse("[{n0, [n1, n2]}, n3]").unwrap()
+        );
+    }
+
+    #[test]
+    fn graphml_round_trip() {
+        for s in ["[]", "A", "{X, [A, B]}", "[{A, B}, {B, C}, {C, A}, D]"] {
+            let expr = Expr::parse(s).unwrap();
+            let read = Expr::from_graphml(&expr.to_graphml()).unwrap();
+            assert_eq!(read.nodes(), expr.nodes());
+            assert_eq!(read.edges(), expr.edges());
+        }
+    }
+
+    #[test]
+    fn graphml_errors() {
+        assert!(matches!(
+            graphml::read("<graphml><graph><node/></graph></graphml>"),
+            Err(Error::MissingAttribute("node", "id"))
+        ));
+        assert!(matches!(
+            graphml::read("<graphml><graph></node></graphml>"),
+            Err(Error::Xml(_))
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use super::Attrs;
+use crate::{Expr, Graph};
+use quick_xml::escape::escape;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+impl Expr {
+    /// Returns the GraphML document for this expression's graph.
+    pub fn to_graphml(&self) -> String {
+        self.to_graphml_with(&Attrs::new())
+    }
+
+    /// Returns the GraphML document for this expression's graph with the
+    /// given node attributes.
+    ///
+    /// Attributes of nodes which aren't in the graph are ignored.
+    pub fn to_graphml_with(&self, attrs: &Attrs) -> String {
+        write(&Graph::from(self), attrs)
+    }
+}
+
+/// Returns the GraphML document for the given graph and node attributes.
+///
+/// Node names are used as their GraphML ids, and each attribute name is
+/// declared as a string `<key>`.
+pub fn write(graph: &Graph, attrs: &Attrs) -> String {
+    let keys: BTreeMap<&str, String> = graph
+        .nodes()
+        .filter_map(|node| attrs.get(node))
+        .flat_map(|attrs| attrs.keys())
+        .map(String::as_str)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name, format!("d{}", i)))
+        .collect();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (name, id) in &keys {
+        writeln!(
+            out,
+            "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>",
+            id,
+            escape(*name)
+        )
+        .unwrap();
+    }
+    out.push_str("  <graph edgedefault=\"undirected\">\n");
+    for node in graph.nodes() {
+        let id = escape(node.as_str());
+        match attrs.get(node).filter(|attrs| !attrs.is_empty()) {
+            Some(attrs) => {
+                writeln!(out, "    <node id=\"{}\">", id).unwrap();
+                for (name, value) in attrs {
+                    writeln!(
+                        out,
+                        "      <data key=\"{}\">{}</data>",
+                        keys[name.as_str()],
+                        escape(value.as_str())
+                    )
+                    .unwrap();
+                }
+                out.push_str("    </node>\n");
+            }
+            None => writeln!(out, "    <node id=\"{}\"/>", id).unwrap(),
+        }
+    }
+    for (a, b) in graph.edges() {
+        writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\"/>",
+            escape(a.as_str()),
+            escape(b.as_str())
+        )
+        .unwrap();
+    }
+    out.push_str("  </graph>\n");
+    out.push_str("</graphml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphml::Attrs;
+    use crate::{Expr, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_graphml() {
+        let mut attrs = Attrs::new();
+        let x = attrs.entry(Node::from("X")).or_default();
+        x.insert("label".into(), "<hub>".into());
+        x.insert("color".into(), "red".into());
+        attrs
+            .entry(Node::from("A"))
+            .or_default()
+            .insert("color".into(), "blue".into());
+        attrs
+            .entry(Node::from("Z"))
+            .or_default()
+            .insert("weight".into(), "1".into());
+
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_graphml_with(&attrs),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color" attr.type="string"/>
+  <key id="d1" for="node" attr.name="label" attr.type="string"/>
+  <graph edgedefault="undirected">
+    <node id="A">
+      <data key="d0">blue</data>
+    </node>
+    <node id="B"/>
+    <node id="X">
+      <data key="d0">red</data>
+      <data key="d1">&lt;hub&gt;</data>
+    </node>
+    <edge source="A" target="X"/>
+    <edge source="B" target="X"/>
+  </graph>
+</graphml>
+"#
+        );
+    }
+}
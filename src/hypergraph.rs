@@ -0,0 +1,132 @@
+//! Hypergraphs, reading each clique of an expression as a single hyperedge.
+//!
+//! A connected group like `{A, B, C}` is a pairwise graph of three edges, but
+//! it's often a single relation between all three nodes, e.g. tasks sharing a
+//! resource. The cliques of the normal form are the hyperedges:
+//!
+//! ```grapl
+//! [{cpu, [build, test]}, {gpu, train}, idle]
+//! => hyperedges
+//! build cpu
+//! cpu test
+//! gpu train
+//! ```
+//!
+//! Since the normal form has no cliques inside other cliques, neither do the
+//! hyperedges. See [`Expr::to_incidence_matrix`] for the incidence matrix.
+
+use crate::graph::alias;
+use crate::{Expr, Graph, Node, Normalize};
+
+impl Expr {
+    /// Returns the hyperedges of this expression: the **sorted** nodes of each
+    /// clique in its normal form, ordered by their nodes.
+    ///
+    /// Nodes without any edges aren't in a hyperedge.
+    pub fn hyperedges(&self) -> Vec<Vec<Node>> {
+        let expr = self.normalize();
+        let cliques = match &expr {
+            Expr::Disconnected(exprs) => exprs.as_slice(),
+            e => std::slice::from_ref(e),
+        };
+        let mut hyperedges: Vec<_> = cliques
+            .iter()
+            .filter(|clique| matches!(clique, Expr::Connected(_)))
+            .map(Expr::nodes)
+            .collect();
+        hyperedges.sort_unstable();
+        hyperedges
+    }
+
+    /// Returns the bipartite incidence graph of this expression's hyperedges.
+    ///
+    /// Each hyperedge becomes a node connected to its own nodes, named `e0`,
+    /// `e1`, ... in the order of [`hyperedges`](Expr::hyperedges). Underscores
+    /// are added to names which are already nodes, like `e0_`:
+    ///
+    /// ```grapl
+    /// [{A, B, C}, D]
+    /// => incidence_graph
+    /// [{e0, [A, B, C]}, D]
+    /// ```
+    pub fn incidence_graph(&self) -> Expr {
+        let graph = Graph::from(self);
+        let mut ds: Vec<_> = self
+            .hyperedges()
+            .into_iter()
+            .enumerate()
+            .map(|(i, nodes)| {
+                let hyperedge = Expr::Node(Node::from(alias(&graph, 'e', i)));
+                let nodes = nodes.into_iter().map(Expr::Node).collect();
+                Expr::Connected(vec![hyperedge, Expr::Disconnected(nodes)])
+            })
+            .collect();
+        ds.extend(
+            graph
+                .nodes()
+                .filter(|node| graph.degree(node) == 0)
+                .map(|node| Expr::Node(node.clone())),
+        );
+        Expr::Disconnected(ds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Node, Normalize, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hyperedges() {
+        assert_eq!(
+            Expr::parse("[{cpu, [build, test]}, {gpu, train}, idle]")
+                .unwrap()
+                .hyperedges(),
+            vec![
+                vec![Node::from("build"), Node::from("cpu")],
+                vec![Node::from("cpu"), Node::from("test")],
+                vec![Node::from("gpu"), Node::from("train")]
+            ]
+        );
+        assert_eq!(
+            Expr::parse("[{A, B}, {C, B, A}]").unwrap().hyperedges(),
+            vec![vec![Node::from("A"), Node::from("B"), Node::from("C")]]
+        );
+        assert_eq!(
+            Expr::parse("(A, B, C)").unwrap().hyperedges(),
+            vec![
+                vec![Node::from("A"), Node::from("B")],
+                vec![Node::from("B"), Node::from("C")]
+            ]
+        );
+        assert_eq!(
+            Expr::parse("[A, B]").unwrap().hyperedges(),
+            vec![] as Vec<Vec<Node>>
+        );
+        assert_eq!(
+            Expr::parse("A").unwrap().hyperedges(),
+            vec![] as Vec<Vec<Node>>
+        );
+    }
+
+    #[test]
+    fn incidence_graph() {
+        let graph = Expr::parse("[{A, B, C}, {C, D}, E]")
+            .unwrap()
+            .incidence_graph();
+        assert_eq!(graph.to_string(), "[{e0, [A, B, C]}, {e1, [C, D]}, E]");
+        assert_eq!(
+            graph.normalize().to_string(),
+            "[{e0, A}, {e0, B}, {e0, C}, {e1, C}, {e1, D}, E]"
+        );
+
+        // Hyperedges don't take the names of nodes.
+        let graph = Expr::parse(r#"[{e0, "{A, B}"}, {A, B}, e1_]"#)
+            .unwrap()
+            .incidence_graph();
+        assert_eq!(
+            graph.to_string(),
+            r#"[{e0_, [A, B]}, {e1, [e0, "{A, B}"]}, e1_]"#
+        );
+    }
+}
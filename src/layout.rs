@@ -0,0 +1,207 @@
+//! Deterministic node positions for drawing graphs.
+//!
+//! Layouts only depend on the graph's sorted nodes and edges, so the same
+//! expression is always drawn the same way.
+
+use crate::{Graph, Node};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::f64::consts::PI;
+
+/// Distance between adjacent nodes in a layout.
+const SPACING: f64 = 1.5;
+
+/// The arrangement of nodes in a drawing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+    /// Nodes evenly spaced clockwise around a circle, starting at the top.
+    #[default]
+    Circular,
+    /// Breadth-first layers of each component from its smallest node, with
+    /// components placed side by side.
+    Layered,
+    /// A force-directed layout, pulling adjacent nodes together and pushing
+    /// all others apart, starting from the circular layout.
+    Force,
+}
+
+impl Layout {
+    /// Returns the position of each node of the graph, with `y` increasing
+    /// upwards.
+    pub fn positions<'g>(&self, graph: &'g Graph) -> BTreeMap<&'g Node, (f64, f64)> {
+        match self {
+            Layout::Circular => circular(graph),
+            Layout::Layered => layered(graph),
+            Layout::Force => force(graph),
+        }
+    }
+}
+
+fn circular(graph: &Graph) -> BTreeMap<&Node, (f64, f64)> {
+    let n = graph.node_count();
+    if n == 1 {
+        return graph.nodes().map(|node| (node, (0.0, 0.0))).collect();
+    }
+    // The radius which keeps neighbors around the circle `SPACING` apart.
+    let radius = (SPACING / 2.0 / (PI / n as f64).sin()).max(1.0);
+    graph
+        .nodes()
+        .enumerate()
+        .map(|(i, node)| {
+            let angle = PI / 2.0 - 2.0 * PI * i as f64 / n as f64;
+            (node, (radius * angle.cos(), radius * angle.sin()))
+        })
+        .collect()
+}
+
+fn layered(graph: &Graph) -> BTreeMap<&Node, (f64, f64)> {
+    let mut positions = BTreeMap::new();
+    let mut offset = 0.0;
+    for component in graph.components() {
+        let layers = layers(graph, &component);
+        let width = layers.iter().map(Vec::len).max().unwrap_or(0);
+        for (depth, layer) in layers.iter().enumerate() {
+            // Center each layer within the component's widest layer.
+            let indent = (width - layer.len()) as f64 / 2.0;
+            for (i, node) in layer.iter().enumerate() {
+                let x = offset + (indent + i as f64) * SPACING;
+                positions.insert(*node, (x, -(depth as f64) * SPACING));
+            }
+        }
+        offset += width as f64 * SPACING;
+    }
+    positions
+}
+
+/// Number of rounds of force-directed movement.
+const ITERATIONS: usize = 200;
+
+// Fruchterman-Reingold with a linearly cooling temperature. Everything is
+// iterated in sorted order, so the result is deterministic.
+fn force(graph: &Graph) -> BTreeMap<&Node, (f64, f64)> {
+    let nodes: Vec<_> = graph.nodes().collect();
+    let start = circular(graph);
+    let mut positions: Vec<_> = nodes.iter().map(|node| start[node]).collect();
+    let index: BTreeMap<_, _> = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+    let edges: Vec<_> = graph.edges().map(|(a, b)| (index[a], index[b])).collect();
+
+    let k = SPACING;
+    let initial = k * (nodes.len() as f64).sqrt();
+    for iteration in 0..ITERATIONS {
+        let mut moves = vec![(0.0, 0.0); nodes.len()];
+        for i in 0..nodes.len() {
+            for j in i + 1..nodes.len() {
+                let (dx, dy, d) = delta(positions[i], positions[j]);
+                let f = k * k / d;
+                moves[i].0 += dx / d * f;
+                moves[i].1 += dy / d * f;
+                moves[j].0 -= dx / d * f;
+                moves[j].1 -= dy / d * f;
+            }
+        }
+        for &(i, j) in &edges {
+            let (dx, dy, d) = delta(positions[i], positions[j]);
+            let f = d * d / k;
+            moves[i].0 -= dx / d * f;
+            moves[i].1 -= dy / d * f;
+            moves[j].0 += dx / d * f;
+            moves[j].1 += dy / d * f;
+        }
+        let temperature = initial * (1.0 - iteration as f64 / ITERATIONS as f64);
+        for (position, (mx, my)) in positions.iter_mut().zip(moves) {
+            let m = (mx * mx + my * my).sqrt().max(f64::EPSILON);
+            let step = m.min(temperature);
+            position.0 += mx / m * step;
+            position.1 += my / m * step;
+        }
+    }
+    nodes.into_iter().zip(positions).collect()
+}
+
+// The offset from b to a and its length, which is never zero.
+fn delta(a: (f64, f64), b: (f64, f64)) -> (f64, f64, f64) {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    (dx, dy, (dx * dx + dy * dy).sqrt().max(0.01))
+}
+
+// Breadth-first layers of the component, visiting neighbors in sorted order.
+fn layers<'g>(graph: &'g Graph, component: &BTreeSet<Node>) -> Vec<Vec<&'g Node>> {
+    let mut layers: Vec<Vec<&Node>> = vec![];
+    let mut seen = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    if let Some(root) = graph.nodes().find(|n| component.contains(*n)) {
+        seen.insert(root);
+        queue.push_back((root, 0));
+    }
+    while let Some((node, depth)) = queue.pop_front() {
+        if layers.len() == depth {
+            layers.push(vec![]);
+        }
+        layers[depth].push(node);
+        for neighbor in graph.neighbors(node) {
+            if seen.insert(neighbor) {
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Layout;
+    use crate::{Expr, Graph, Node, Parse};
+
+    fn positions(s: &str, layout: Layout) -> Vec<(String, (f64, f64))> {
+        let graph = Graph::from(&Expr::parse(s).unwrap());
+        layout
+            .positions(&graph)
+            .into_iter()
+            .map(|(node, (x, y))| (node.to_string(), ((x * 100.0).round(), (y * 100.0).round())))
+            .collect()
+    }
+
+    #[test]
+    fn circular_layout() {
+        assert_eq!(
+            positions("A", Layout::Circular),
+            vec![("A".into(), (0.0, 0.0))]
+        );
+        assert_eq!(
+            positions("[A, B, C, D]", Layout::Circular),
+            vec![
+                ("A".into(), (0.0, 106.0)),
+                ("B".into(), (106.0, 0.0)),
+                ("C".into(), (0.0, -106.0)),
+                ("D".into(), (-106.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn force_layout() {
+        let graph = Graph::from(&Expr::parse("[{A, B}, {B, C}, {C, D}]").unwrap());
+        let positions = Layout::Force.positions(&graph);
+        assert_eq!(positions, Layout::Force.positions(&graph));
+        let distance = |a: &str, b: &str| {
+            let (a, b) = (positions[&Node::from(a)], positions[&Node::from(b)]);
+            ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+        };
+        // The path is stretched out, with its ends furthest apart.
+        assert!(distance("A", "B") < distance("A", "C"));
+        assert!(distance("A", "C") < distance("A", "D"));
+    }
+
+    #[test]
+    fn layered_layout() {
+        assert_eq!(
+            positions("[{A, [B, C]}, {C, D}, E]", Layout::Layered),
+            vec![
+                ("A".into(), (75.0, 0.0)),
+                ("B".into(), (0.0, -150.0)),
+                ("C".into(), (150.0, -150.0)),
+                ("D".into(), (75.0, -300.0)),
+                ("E".into(), (300.0, 0.0)),
+            ]
+        );
+    }
+}
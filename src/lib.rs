@@ -1,8 +1,9 @@
+use crate::limit::{Budget, LimitExceeded, Limits};
 use chumsky::prelude::*;
 use itertools::Itertools;
-#[cfg(feature = "petgraph")]
-use petgraph::Graph;
+use std::borrow::Borrow;
 use std::hash::Hash;
+use std::sync::Arc;
 
 /// Parsing for syntax elements.
 ///
@@ -23,17 +24,90 @@ where
 /// Nodes used as base indentifiers or to refer to other graphs.
 ///
 /// Examples of nodes: `A`, `a`, `G1`...
+///
+/// Node names are shared, so cloning a node is cheap and never copies its
+/// name. With the `serde` feature nodes serialize as their name.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Node(String);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Node(Arc<str>);
+
+impl Node {
+    /// Create a node with the given name.
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Node(name.into())
+    }
+
+    /// Returns the name of this node.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
 
+impl From<&str> for Node {
+    fn from(name: &str) -> Self {
+        Node::new(name)
+    }
+}
+
+impl From<String> for Node {
+    fn from(name: String) -> Self {
+        Node::new(name)
+    }
+}
+
+impl AsRef<str> for Node {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Node {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Nodes are plain identifiers, or quoted to use any other name, e.g.
+/// `"(A, X)"`.
 impl<'src> Parse<'src> for Node {
     fn parser() -> impl Parser<'src, &'src str, Self> + Clone {
-        text::ascii::ident()
-            .padded()
-            .map(|t: &str| Node(t.to_string()))
+        let quoted = none_of("\\\"")
+            .or(just('\\').ignore_then(any()))
+            .repeated()
+            .at_least(1)
+            .collect::<String>()
+            .delimited_by(just('"'), just('"'))
+            .map(Node::from);
+        choice((text::ascii::ident().map(Node::from), quoted)).padded()
+    }
+}
+
+impl Node {
+    // Writes the node as it's parsed, quoting names which aren't plain
+    // identifiers.
+    fn fmt_syntax(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if is_identifier(&self.0) {
+            write!(f, "{}", self.0)
+        } else {
+            write!(
+                f,
+                "\"{}\"",
+                self.0.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        }
     }
 }
 
+// Returns true for plain alphanumeric names like `web1` or `_a`, which most
+// output formats also write without quotes.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 impl std::fmt::Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -46,71 +120,274 @@ impl std::fmt::Display for Node {
 /// { A, B }
 /// { A, [B, C] }
 /// ```
+///
+/// Families of numbered nodes are written as inclusive ranges, which expand to
+/// a disconnected list of nodes when parsed. A leading zero pads the numbers:
+///
+/// ```grapl
+/// {lb, web[1..20]}
+/// db[01..03]
+/// => [db01, db02, db03]
+/// ```
+///
+/// Paths and cycles through a sequence of expressions have their own literals,
+/// with consecutive expressions connected and a trailing `+` closing the
+/// cycle. It isn't a trailing `*`, since `(A, B) * C` is a [`Product`]:
+///
+/// ```grapl
+/// (A, B, C, D)
+/// (A, B, C, D)+
+/// ```
+///
+/// Two expressions can also be multiplied with a graph [`Product`]:
+///
+/// ```grapl
+/// {A, B} * {X, Y}
+/// ```
+///
+/// Part of a graph is selected with a [`Subgraph`] operator, keeping only the
+/// nodes of another expression, removing them, or removing its edges:
+///
+/// ```grapl
+/// G | [A, B, C]
+/// G \ X
+/// {A, B, C, D} - (A, D)
+/// ```
+///
+/// A [`Comprehension`] generates an expression for each combination of nodes
+/// from other graphs:
+///
+/// ```grapl
+/// [{x, y} for x in G1, y in G2 if x != y]
+/// ```
+///
+/// With the `serde` feature expressions serialize as nested variants, e.g.
+/// `{"connected": [{"node": "A"}, {"node": "B"}]}`. Use [`Graph`] for a flat
+/// list of nodes and edges instead.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Expr {
     Node(Node),
     Connected(Vec<Expr>),
     Disconnected(Vec<Expr>),
+    Path(Vec<Expr>),
+    Cycle(Vec<Expr>),
+    Product(Product, Box<Expr>, Box<Expr>),
+    Subgraph(Subgraph, Box<Expr>, Box<Expr>),
+    Comprehension(Box<Comprehension>),
 }
 
+/// The most nodes a single range like `web[1..20]` can expand to.
+pub const MAX_RANGE: u64 = 100_000;
+
 impl<'src> Parse<'src> for Expr {
     fn parser() -> impl Parser<'src, &'src str, Self> + Clone {
         recursive(|expr| {
             let node = Node::parser().map(Expr::Node);
 
+            let digits = text::digits(10).at_least(1).to_slice();
+            let range = text::ascii::ident()
+                .then(
+                    digits
+                        .then_ignore(just(".."))
+                        .then(digits)
+                        .delimited_by(just('['), just(']')),
+                )
+                .try_map(|(prefix, (start, end)): (&str, (&str, &str)), _| {
+                    let error = || EmptyErr::default();
+                    let first: u64 = start.parse().map_err(|_| error())?;
+                    let last: u64 = end.parse().map_err(|_| error())?;
+                    if first > last || last - first >= MAX_RANGE {
+                        return Err(error());
+                    }
+                    // web[08..10] => [web08, web09, web10]
+                    let width = if start.starts_with('0') {
+                        start.len()
+                    } else {
+                        0
+                    };
+                    let nodes = (first..=last)
+                        .map(|n| Expr::Node(Node::from(format!("{prefix}{n:0width$}"))))
+                        .collect();
+                    Ok(Expr::Disconnected(nodes))
+                })
+                .padded();
+
             let seq = expr
                 .clone()
                 .separated_by(just(",").padded())
                 .allow_trailing()
                 .collect::<Vec<_>>();
 
-            let connected = seq
+            // [{x, y} for x in G1, y in G2 if x != y]
+            let generators = Node::parser()
+                .then_ignore(text::ascii::keyword("in").padded())
+                .then(expr.clone())
+                .separated_by(just(',').padded())
+                .at_least(1)
+                .collect::<Vec<_>>();
+            let condition = Node::parser()
+                .then(choice((just("=="), just("!="), just("<"))).padded())
+                .then(Node::parser())
+                .map(|((a, op), b)| match op {
+                    "==" => Condition::Eq(a, b),
+                    "!=" => Condition::Ne(a, b),
+                    _ => Condition::Lt(a, b),
+                });
+            let conditions = text::ascii::keyword("if")
+                .padded()
+                .ignore_then(
+                    condition
+                        .separated_by(text::ascii::keyword("and").padded())
+                        .at_least(1)
+                        .collect::<Vec<_>>(),
+                )
+                .or_not()
+                .map(Option::unwrap_or_default);
+            // A comprehension is a group with a single expression and a `for`
+            // suffix. Both share one parse of the group, so nested brackets
+            // aren't parsed again when there's no suffix.
+            let group = seq
                 .clone()
-                .delimited_by(just('{'), just('}'))
-                .map(Expr::Connected);
-
-            let disconnected = seq
+                .then(
+                    text::ascii::keyword("for")
+                        .padded()
+                        .ignore_then(generators)
+                        .then(conditions)
+                        .or_not(),
+                )
+                .boxed();
+            let group = |connected: bool| {
+                group
+                    .clone()
+                    .try_map(move |(exprs, suffix), _| match suffix {
+                        None if connected => Ok(Expr::Connected(exprs)),
+                        None => Ok(Expr::Disconnected(exprs)),
+                        Some((gens, conds)) => match <[Expr; 1]>::try_from(exprs) {
+                            Ok([body]) => Ok(Expr::Comprehension(Box::new(Comprehension::new(
+                                connected, body, gens, conds,
+                            )))),
+                            Err(_) => Err(EmptyErr::default()),
+                        },
+                    })
+            };
+
+            let connected = group(true).delimited_by(just('{'), just('}'));
+
+            let disconnected = group(false).delimited_by(just('['), just(']'));
+
+            // `*` already multiplies graphs, so cycles are marked with `+`.
+            let path = seq
                 .clone()
-                .delimited_by(just('['), just(']'))
-                .map(Expr::Disconnected);
-
-            choice((node, connected, disconnected)).padded()
+                .delimited_by(just('('), just(')'))
+                .then(just('+').or_not())
+                .map(|(exprs, cycle)| match cycle {
+                    Some(_) => Expr::Cycle(exprs),
+                    None => Expr::Path(exprs),
+                });
+
+            let atom = choice((range, node, connected, disconnected, path)).padded();
+
+            // Products and subgraphs are left associative, all with the same
+            // precedence.
+            #[derive(Clone)]
+            enum Op {
+                Product(Product),
+                Subgraph(Subgraph),
+            }
+            let op = choice((
+                just("**").to(Op::Product(Product::Strong)),
+                just("*").to(Op::Product(Product::Cartesian)),
+                just("&").to(Op::Product(Product::Tensor)),
+                just("|").to(Op::Subgraph(Subgraph::Induced)),
+                just("\\").to(Op::Subgraph(Subgraph::Without)),
+                just("-").to(Op::Subgraph(Subgraph::Difference)),
+            ));
+            atom.clone()
+                .foldl(op.then(atom).repeated(), |a, (op, b)| match op {
+                    Op::Product(product) => Expr::Product(product, Box::new(a), Box::new(b)),
+                    Op::Subgraph(subgraph) => Expr::Subgraph(subgraph, Box::new(a), Box::new(b)),
+                })
         })
     }
 }
 
 impl<'src> Expr {
     /// Retrurns the **sorted** list of nodes for this expression.
+    ///
+    /// Products and comprehensions can have many more nodes than the
+    /// expression, see [`Expr::try_nodes_with`] to bound the work.
     pub fn nodes(&self) -> Vec<Node> {
+        self.try_nodes_with(&Limits::default())
+            .expect("unlimited budget exceeded")
+    }
+
+    /// Returns the **sorted** list of nodes for this expression, within the
+    /// given limits.
+    pub fn try_nodes_with(&self, limits: &Limits) -> Result<Vec<Node>, LimitExceeded> {
+        self.nodes_within(&Budget::new(limits))
+    }
+
+    pub(crate) fn nodes_within(&self, budget: &Budget) -> Result<Vec<Node>, LimitExceeded> {
+        let mut nodes = vec![];
+        self.collect_nodes(&mut nodes, budget)?;
+        nodes.sort_unstable();
+        nodes.dedup();
+        Ok(nodes)
+    }
+
+    fn collect_nodes(&self, nodes: &mut Vec<Node>, budget: &Budget) -> Result<(), LimitExceeded> {
         match self {
-            Expr::Node(node) => vec![node.clone()],
-            Expr::Connected(exprs) | Expr::Disconnected(exprs) => exprs
-                .iter()
-                .fold(vec![], |mut v, e| {
-                    v.append(&mut e.nodes());
-                    v
-                })
-                .into_iter()
-                .sorted()
-                .dedup()
-                .collect(),
+            Expr::Node(node) => nodes.push(node.clone()),
+            Expr::Connected(exprs)
+            | Expr::Disconnected(exprs)
+            | Expr::Path(exprs)
+            | Expr::Cycle(exprs) => {
+                for expr in exprs {
+                    expr.collect_nodes(nodes, budget)?;
+                }
+            }
+            Expr::Product(_, a, b) => {
+                let xs = b.nodes_within(budget)?;
+                let ys = a.nodes_within(budget)?;
+                budget.nodes(
+                    nodes
+                        .len()
+                        .saturating_add(xs.len().saturating_mul(ys.len())),
+                )?;
+                for a in ys {
+                    nodes.extend(xs.iter().map(|x| product::pair(&a, x)));
+                }
+            }
+            Expr::Subgraph(subgraph, a, b) => nodes.extend(subgraph.nodes(a, b, budget)?),
+            Expr::Comprehension(c) => c.expand(budget)?.collect_nodes(nodes, budget)?,
         }
+        Ok(())
     }
 
     /// Retrurns the **sorted** list of edges for this expression.
     pub fn edges(&self) -> Vec<(Node, Node)> {
         match self.normalize() {
             Self::Node(_) => vec![],
+            // Paths, cycles, products, subgraphs and comprehensions normalize
+            // into the cliques of the graph they describe.
+            Self::Path(_)
+            | Self::Cycle(_)
+            | Self::Product(..)
+            | Self::Subgraph(..)
+            | Self::Comprehension(_) => unreachable!(),
             // TODO: directed vs undirected...
-            expr @ Self::Connected(_) => expr
-                .nodes()
-                .iter()
-                .cartesian_product(expr.nodes().iter())
-                .map(|(a, b)| (a.clone(), b.clone()))
-                .sorted()
-                .filter(|(a, b)| a != b)
-                .dedup()
-                .collect(),
+            expr @ Self::Connected(_) => {
+                let nodes = expr.nodes();
+                // Nodes are sorted and unique, so the product is as well.
+                nodes
+                    .iter()
+                    .cartesian_product(nodes.iter())
+                    .filter(|(a, b)| a != b)
+                    .map(|(a, b)| (a.clone(), b.clone()))
+                    .collect()
+            }
             Self::Disconnected(exprs) => {
                 let mut edges = vec![];
                 for expr in exprs {
@@ -125,35 +402,18 @@ impl<'src> Expr {
     pub fn contains(&self, node: &Node) -> bool {
         match self {
             Expr::Node(n) => node == n,
-            Expr::Connected(exprs) | Expr::Disconnected(exprs) => {
-                exprs.iter().any(|e| e.contains(node))
+            Expr::Connected(exprs)
+            | Expr::Disconnected(exprs)
+            | Expr::Path(exprs)
+            | Expr::Cycle(exprs) => exprs.iter().any(|e| e.contains(node)),
+            Expr::Product(_, a, b) | Expr::Subgraph(_, a, b) => {
+                a.contains(node) || b.contains(node)
             }
+            Expr::Comprehension(c) => c.contains(node),
         }
     }
 }
 
-#[cfg(feature = "petgraph")]
-impl Into<Graph<Node, ()>> for &Expr {
-    fn into(self) -> Graph<Node, ()> {
-        let mut graph: Graph<Node, _> = Graph::new();
-        for node in self.nodes() {
-            graph.add_node(node);
-        }
-        for (a, b) in self.edges() {
-            let ia = graph
-                .node_indices()
-                .find(|idx| a == *graph.node_weight(*idx).unwrap())
-                .unwrap();
-            let ib = graph
-                .node_indices()
-                .find(|idx| b == *graph.node_weight(*idx).unwrap())
-                .unwrap();
-            graph.add_edge(ia, ib, ());
-        }
-        graph
-    }
-}
-
 impl<'src> std::fmt::Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let joined = |exprs: &[Expr]| {
@@ -164,13 +424,32 @@ impl<'src> std::fmt::Display for Expr {
                 .join(", ")
         };
         match self {
-            Expr::Node(node) => write!(f, "{}", node),
+            Expr::Node(node) => node.fmt_syntax(f),
             Expr::Connected(exprs) => write!(f, "{{{}}}", joined(&exprs)),
             Expr::Disconnected(exprs) => write!(f, "[{}]", joined(&exprs)),
+            Expr::Path(exprs) => write!(f, "({})", joined(exprs)),
+            Expr::Cycle(exprs) => write!(f, "({})+", joined(exprs)),
+            Expr::Product(product, a, b) => fmt_op(f, product, a, b),
+            Expr::Subgraph(subgraph, a, b) => fmt_op(f, subgraph, a, b),
+            Expr::Comprehension(c) => write!(f, "{}", c),
         }
     }
 }
 
+// Operators are left associative, so an operator on the right needs grouping.
+fn fmt_op(
+    f: &mut std::fmt::Formatter<'_>,
+    op: &impl std::fmt::Display,
+    a: &Expr,
+    b: &Expr,
+) -> std::fmt::Result {
+    if matches!(b, Expr::Product(..) | Expr::Subgraph(..)) {
+        write!(f, "{} {} {{{}}}", a, op, b)
+    } else {
+        write!(f, "{} {} {}", a, op, b)
+    }
+}
+
 /// A statement is part of a sequence used to resolve other statements.
 ///
 /// ```grapl
@@ -179,6 +458,8 @@ impl<'src> std::fmt::Display for Expr {
 /// G  = {G1, G2}
 /// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Stmt {
     Assign(Node, Expr),
 }
@@ -203,7 +484,10 @@ impl<'src> Parse<'src> for Vec<Stmt> {
 impl<'src> std::fmt::Display for Stmt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.normalize() {
-            Stmt::Assign(node, expr) => write!(f, "{} = {}", node, expr),
+            Stmt::Assign(node, expr) => {
+                node.fmt_syntax(f)?;
+                write!(f, " = {}", expr)
+            }
         }
     }
 }
@@ -215,6 +499,7 @@ impl<'src> std::fmt::Display for Stmt {
 /// {G, [C, D]}
 /// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ret(Vec<Stmt>, Expr);
 
 impl<'src> Parse<'src> for Ret {
@@ -238,12 +523,56 @@ impl<'src> std::fmt::Display for Ret {
     }
 }
 
-mod normal;
+pub mod adjacency;
+
+#[cfg(feature = "petgraph")]
+pub mod algo;
+
+pub mod comprehension;
+pub use self::comprehension::{Comprehension, Condition};
+
+#[cfg(feature = "petgraph")]
+pub mod convert;
+
+pub mod dot;
+
+pub mod graph;
+pub use self::graph::Graph;
+
+#[cfg(feature = "graphml")]
+pub mod graphml;
+
+pub mod hypergraph;
+
+pub mod layout;
+
+pub mod limit;
+
+pub mod mermaid;
+
+pub mod multigraph;
+pub use self::multigraph::Multigraph;
+
+pub mod normal;
 pub use self::normal::Normalize;
 
+pub mod plantuml;
+
+pub mod product;
+pub use self::product::Product;
+
 pub mod resolve;
 pub use self::resolve::Resolve;
 
+pub mod subgraph;
+pub use self::subgraph::Subgraph;
+
+pub mod svg;
+
+pub mod table;
+
+pub mod tikz;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,6 +589,23 @@ mod tests {
         assert!(Node::parse("").has_errors());
         assert!(Node::parse("1").has_errors());
         assert_eq!(Node::parse("A").into_result(), Ok(node!(A)));
+        assert_eq!(
+            Node::parse(r#" "(A, \"B\")" "#).into_result(),
+            Ok(Node::from(r#"(A, "B")"#))
+        );
+        assert!(Node::parse(r#""""#).has_errors());
+    }
+
+    #[test]
+    fn shared_node() {
+        let node = Node::parse("A").unwrap();
+        let clone = node.clone();
+        assert!(Arc::ptr_eq(&node.0, &clone.0));
+        assert_eq!(node, Node::from("A"));
+        assert_eq!(node.as_str(), "A");
+
+        let nodes: std::collections::HashSet<Node> = [node].into();
+        assert!(nodes.contains("A"));
     }
 
     #[test]
@@ -315,7 +661,45 @@ mod tests {
                 Expr::Connected(vec![enode!(A), enode!(B)]),
                 Expr::Disconnected(vec![enode!(C), enode!(D)])
             ]))
-        )
+        );
+        assert_eq!(
+            Expr::parse("(A, B, C)").into_result(),
+            Ok(Expr::Path(vec![enode!(A), enode!(B), enode!(C)]))
+        );
+        assert_eq!(
+            Expr::parse("(A, [B, C], D)+").into_result(),
+            Ok(Expr::Cycle(vec![
+                enode!(A),
+                Expr::Disconnected(vec![enode!(B), enode!(C)]),
+                enode!(D)
+            ]))
+        );
+        assert!(Expr::parse("(A, B) +").has_errors());
+        assert!(matches!(
+            Expr::parse("(A, B)*C").unwrap(),
+            Expr::Product(Product::Cartesian, _, _)
+        ));
+    }
+
+    #[test]
+    fn parse_range() {
+        assert_eq!(
+            Expr::parse("{lb, web[1..3]}").into_result(),
+            Ok(Expr::Connected(vec![
+                enode!(lb),
+                Expr::Disconnected(vec![enode!(web1), enode!(web2), enode!(web3)])
+            ]))
+        );
+        assert_eq!(
+            Expr::parse("n[08..10]").unwrap().to_string(),
+            "[n08, n09, n10]"
+        );
+        assert_eq!(Expr::parse("n[5..5]").unwrap().to_string(), "[n5]");
+        assert_eq!(Expr::parse("web[1..20]").unwrap().nodes().len(), 20);
+        assert!(Expr::parse("n[3..1]").has_errors());
+        assert!(Expr::parse("n[1..]").has_errors());
+        assert!(Expr::parse("n[0..100000]").has_errors());
+        assert_eq!(Expr::parse("n[1..100000]").unwrap().nodes().len(), 100_000);
     }
 
     #[test]
@@ -354,6 +738,23 @@ mod tests {
             Expr::parser().parse("[{A,B}, {A,B}]").unwrap().edges(),
             vec![(node!(A), node!(B)), (node!(B), node!(A)),]
         );
+        assert_eq!(
+            Expr::parse("(A, B, C)").unwrap().edges(),
+            vec![
+                (node!(A), node!(B)),
+                (node!(B), node!(A)),
+                (node!(B), node!(C)),
+                (node!(C), node!(B)),
+            ]
+        );
+        assert_eq!(
+            Expr::parse("(A, B, C)+").unwrap().edges(),
+            Expr::parse("{A, B, C}").unwrap().edges()
+        );
+        assert_eq!(
+            Expr::parse("(A, B)+").unwrap().edges(),
+            Expr::parse("(A, B)").unwrap().edges()
+        );
     }
 
     #[test]
@@ -377,7 +778,25 @@ mod tests {
                 .unwrap()
                 .to_string(),
             "{A, {B, [C, D]}}"
-        )
+        );
+        assert_eq!(
+            Expr::parse(r#"[A, "web 1", "say \"hi\"", "a\\b"]"#)
+                .unwrap()
+                .to_string(),
+            r#"[A, "web 1", "say \"hi\"", "a\\b"]"#
+        );
+        assert_eq!(
+            Expr::parse("A * {B & C}").unwrap().to_string(),
+            "A * {B & C}"
+        );
+        for s in [
+            "(A, B, C, D)",
+            "(A, B, C, D)+",
+            "()",
+            "(A, [B, C])+ * (X, Y)",
+        ] {
+            assert_eq!(Expr::parse(s).unwrap().to_string(), s);
+        }
     }
 
     #[test]
@@ -483,4 +902,24 @@ mod tests {
     fn display_ret() {
         assert_eq!(Ret::parse("  G=A B").unwrap().to_string(), "G = A\nB")
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let expr = Expr::parse("{X, [A, B]}").unwrap();
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(
+            json,
+            r#"{"connected":[{"node":"X"},{"disconnected":[{"node":"A"},{"node":"B"}]}]}"#
+        );
+        assert_eq!(serde_json::from_str::<Expr>(&json).unwrap(), expr);
+
+        let ret = Ret::parse("G = {A, B} {G, C}").unwrap();
+        let json = serde_json::to_string(&ret).unwrap();
+        assert_eq!(
+            json,
+            r#"[[{"assign":["G",{"connected":[{"node":"A"},{"node":"B"}]}]}],{"connected":[{"node":"G"},{"node":"C"}]}]"#
+        );
+        assert_eq!(serde_json::from_str::<Ret>(&json).unwrap(), ret);
+    }
 }
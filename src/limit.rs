@@ -0,0 +1,215 @@
+//! Resource limits for normalization and resolution.
+//!
+//! Both [`Normalize`](crate::Normalize) and [`Resolve`](crate::Resolve) can
+//! produce output exponentially larger than their input, e.g. `{[A, B], [C,
+//! D], [E, F], ...}`. A [`Limits`] set on their configurations bounds this work
+//! and reports [`LimitExceeded`] instead of hanging or exhausting memory.
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Budgets for a single normalization or resolution.
+///
+/// All limits are unset by default.
+///
+/// ```
+/// use grapl::limit::Limits;
+/// use std::time::Duration;
+///
+/// let limits = Limits::default()
+///     .with_cliques(10_000)
+///     .with_timeout(Duration::from_secs(1));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    nodes: Option<usize>,
+    cliques: Option<usize>,
+    depth: Option<usize>,
+    steps: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+impl Limits {
+    /// Limit the total number of nodes in the output.
+    pub fn with_nodes(mut self, nodes: usize) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+
+    /// Limit the number of cliques in a normalized output.
+    pub fn with_cliques(mut self, cliques: usize) -> Self {
+        self.cliques = Some(cliques);
+        self
+    }
+
+    /// Limit the nesting depth of expressions being processed.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Limit the number of sub-expressions processed.
+    pub fn with_steps(mut self, steps: usize) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Limit the wall-clock time spent.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl fmt::Display for Limits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limits = vec![];
+        if let Some(nodes) = self.nodes {
+            limits.push(format!("nodes {}", nodes));
+        }
+        if let Some(cliques) = self.cliques {
+            limits.push(format!("cliques {}", cliques));
+        }
+        if let Some(depth) = self.depth {
+            limits.push(format!("depth {}", depth));
+        }
+        if let Some(steps) = self.steps {
+            limits.push(format!("steps {}", steps));
+        }
+        if let Some(timeout) = self.timeout {
+            limits.push(format!("time {}", timeout.as_secs_f64()));
+        }
+        if limits.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", limits.join(" "))
+        }
+    }
+}
+
+/// The kind of limit which was exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Nodes,
+    Cliques,
+    Depth,
+    Steps,
+    Timeout,
+}
+
+/// Error returned when one of the configured [`Limits`] is exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LimitExceeded(pub Limit);
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = match self.0 {
+            Limit::Nodes => "node",
+            Limit::Cliques => "clique",
+            Limit::Depth => "depth",
+            Limit::Steps => "step",
+            Limit::Timeout => "time",
+        };
+        write!(f, "{} limit exceeded", limit)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// Running usage of a set of [`Limits`].
+///
+/// Budgets are shared between threads when normalizing in parallel.
+#[derive(Debug)]
+pub(crate) struct Budget<'l> {
+    limits: &'l Limits,
+    steps: AtomicUsize,
+    start: Instant,
+}
+
+impl<'l> Budget<'l> {
+    pub(crate) fn new(limits: &'l Limits) -> Self {
+        Budget {
+            limits,
+            steps: AtomicUsize::new(0),
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn limits(&self) -> &Limits {
+        self.limits
+    }
+
+    /// Counts a single step, checking both the step and time limits.
+    pub(crate) fn step(&self) -> Result<(), LimitExceeded> {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        check(self.limits.steps, steps, Limit::Steps)?;
+        match self.limits.timeout {
+            Some(timeout) if self.start.elapsed() > timeout => Err(LimitExceeded(Limit::Timeout)),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn nodes(&self, nodes: usize) -> Result<(), LimitExceeded> {
+        check(self.limits.nodes, nodes, Limit::Nodes)
+    }
+
+    pub(crate) fn cliques(&self, cliques: usize) -> Result<(), LimitExceeded> {
+        check(self.limits.cliques, cliques, Limit::Cliques)
+    }
+
+    pub(crate) fn depth(&self, depth: usize) -> Result<(), LimitExceeded> {
+        check(self.limits.depth, depth, Limit::Depth)
+    }
+}
+
+fn check(limit: Option<usize>, value: usize, kind: Limit) -> Result<(), LimitExceeded> {
+    match limit {
+        Some(limit) if value > limit => Err(LimitExceeded(kind)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, Limit, LimitExceeded, Limits};
+    use std::time::Duration;
+
+    #[test]
+    fn unlimited_budget() {
+        let limits = Limits::default();
+        let budget = Budget::new(&limits);
+        for _ in 0..1000 {
+            assert_eq!(budget.step(), Ok(()));
+        }
+        assert_eq!(budget.nodes(usize::MAX), Ok(()));
+        assert_eq!(budget.cliques(usize::MAX), Ok(()));
+        assert_eq!(budget.depth(usize::MAX), Ok(()));
+    }
+
+    #[test]
+    fn exceeded_budget() {
+        let limits = Limits::default()
+            .with_nodes(10)
+            .with_cliques(5)
+            .with_depth(2)
+            .with_steps(3);
+        let budget = Budget::new(&limits);
+        assert_eq!(budget.nodes(10), Ok(()));
+        assert_eq!(budget.nodes(11), Err(LimitExceeded(Limit::Nodes)));
+        assert_eq!(budget.cliques(6), Err(LimitExceeded(Limit::Cliques)));
+        assert_eq!(budget.depth(3), Err(LimitExceeded(Limit::Depth)));
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Err(LimitExceeded(Limit::Steps)));
+    }
+
+    #[test]
+    fn timeout_budget() {
+        let limits = Limits::default().with_timeout(Duration::ZERO);
+        let budget = Budget::new(&limits);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(budget.step(), Err(LimitExceeded(Limit::Timeout)));
+    }
+}
@@ -1,48 +1,55 @@
 use chumsky::prelude::*;
-#[cfg(feature = "petgraph")]
-use grapl::Node;
+use grapl::dot;
+use grapl::limit::Limits;
+use grapl::normal::{self, Form};
 use grapl::resolve::{Config, Env};
-use grapl::{Expr, Normalize, Parse, Resolve, Stmt};
+use grapl::{Expr, Node, Normalize, Parse, Resolve, Stmt};
 use microxdg::{Xdg, XdgError};
-#[cfg(feature = "petgraph")]
-use petgraph::{
-    dot::{Config as DotConfig, Dot},
-    graph::Graph,
-};
 use rustyline::error::ReadlineError;
 use rustyline::history::FileHistory;
 use rustyline::{DefaultEditor, Editor};
 use std::fs;
-#[cfg(feature = "petgraph")]
-use std::fs::File;
-#[cfg(feature = "petgraph")]
-use std::io::prelude::*;
 use std::path::PathBuf;
+use std::time::Duration;
 
 fn main() -> rustyline::Result<()> {
     let mut rl = DefaultEditor::new()?;
     load_history(&mut rl);
 
-    let config = Config::default().with_shadowing();
-    let mut env = Env::new(&config);
+    let mut limits = Limits::default()
+        .with_nodes(1_000_000)
+        .with_timeout(Duration::from_secs(10));
+    let mut norm = normal::Config::default().with_limits(limits.clone());
+    let mut bindings = vec![];
 
-    loop {
-        let readline = rl.readline("> ");
-        match readline {
-            Ok(line) => {
-                handle_line(line, &mut env, &mut rl);
-            }
-            Err(ReadlineError::Interrupted) => {
-                println!("Ctrl-C pressed. Exiting.");
-                break;
-            }
-            Err(ReadlineError::Eof) => {
-                println!("Ctrl-D pressed. Exiting.");
-                break;
-            }
-            Err(err) => {
-                println!("Error: {:?}", err);
-                break;
+    'session: loop {
+        // The environment borrows its configuration, so it's rebuilt with the
+        // same bindings whenever `!limit` changes the limits.
+        let config = resolve_config(&limits);
+        let mut env = Env::from_bindings(&config, bindings).expect("bindings are already resolved");
+        loop {
+            let readline = rl.readline("> ");
+            match readline {
+                Ok(line) => {
+                    if let Some(fresh) = handle_line(line, &mut env, &mut norm, &limits, &mut rl) {
+                        norm = norm.with_limits(fresh.clone());
+                        limits = fresh;
+                        bindings = env_bindings(&env);
+                        continue 'session;
+                    }
+                }
+                Err(ReadlineError::Interrupted) => {
+                    println!("Ctrl-C pressed. Exiting.");
+                    break 'session;
+                }
+                Err(ReadlineError::Eof) => {
+                    println!("Ctrl-D pressed. Exiting.");
+                    break 'session;
+                }
+                Err(err) => {
+                    println!("Error: {:?}", err);
+                    break 'session;
+                }
             }
         }
     }
@@ -60,39 +67,156 @@ enum Input {
 
 enum Cmd {
     Env,
-    #[cfg(feature = "petgraph")]
+    Normalize(Option<normal::Config>),
+    Limit(Vec<LimitOption>),
     Viz(Expr, Option<PathBuf>),
+    Dot(Expr),
+    Import(PathBuf),
+    Mermaid(Expr),
+    PlantUml(Expr),
+    Algo(Algo, Expr),
+}
+
+#[derive(Clone)]
+#[cfg_attr(not(feature = "petgraph"), allow(dead_code))]
+enum Algo {
+    Components,
+    Path(Node, Node),
+    Degree,
+    Bipartite,
+    Cycles,
+    Color,
 }
 
 impl Cmd {
     fn parser<'src>() -> impl Parser<'src, &'src str, Cmd> {
         let env = just("!env").padded().map(|_| Cmd::Env);
 
-        #[cfg(feature = "petgraph")]
-        {
-            let path = any().repeated().collect().map(|p: String| {
-                if p == "" {
-                    None
-                } else {
-                    Some(PathBuf::from(p))
+        let norm_option = choice((
+            just("dnf").to(NormOption::Form(Form::Dnf)),
+            just("factored").to(NormOption::Form(Form::Factored)),
+            just("dups").to(NormOption::Duplicates),
+            just("sort").to(NormOption::Sorting),
+        ))
+        .padded();
+        let norm = just("!normalize")
+            .padded()
+            .ignore_then(norm_option.repeated().collect::<Vec<_>>())
+            .map(|options| {
+                if options.is_empty() {
+                    return Cmd::Normalize(None);
                 }
+                let config = options
+                    .into_iter()
+                    .fold(normal::Config::default(), |c, o| match o {
+                        NormOption::Form(form) => c.with_form(form),
+                        NormOption::Duplicates => c.with_duplicates(),
+                        NormOption::Sorting => c.with_sorting(),
+                    });
+                Cmd::Normalize(Some(config))
             });
-            let viz = just("!viz ")
-                .then(Expr::parser())
+
+        let count = text::int(10).try_map(|n: &str, _| n.parse().map_err(|_| EmptyErr::default()));
+        let limit_option = choice((
+            just("none").to(LimitOption::None),
+            just("nodes")
+                .padded()
+                .ignore_then(count)
+                .map(LimitOption::Nodes),
+            just("cliques")
+                .padded()
+                .ignore_then(count)
+                .map(LimitOption::Cliques),
+            just("depth")
+                .padded()
+                .ignore_then(count)
+                .map(LimitOption::Depth),
+            just("steps")
+                .padded()
+                .ignore_then(count)
+                .map(LimitOption::Steps),
+            just("time")
                 .padded()
-                .then(path)
-                .map(|((_, e), p)| Cmd::Viz(e, p));
+                .ignore_then(count)
+                .map(LimitOption::Time),
+        ))
+        .padded();
+        let limit = just("!limit")
+            .padded()
+            .ignore_then(limit_option.repeated().collect::<Vec<_>>())
+            .map(Cmd::Limit);
 
-            env.or(viz)
-        }
+        let path = any().repeated().collect().map(|p: String| {
+            if p == "" {
+                None
+            } else {
+                Some(PathBuf::from(p))
+            }
+        });
+        let viz = just("!viz ")
+            .then(Expr::parser())
+            .padded()
+            .then(path)
+            .map(|((_, e), p)| Cmd::Viz(e, p));
 
-        #[cfg(not(feature = "petgraph"))]
-        {
-            env
-        }
+        let dot = just("!dot ")
+            .ignore_then(Expr::parser())
+            .padded()
+            .map(Cmd::Dot);
+        let import = just("!import ")
+            .ignore_then(path)
+            .try_map(|p, _| p.ok_or(EmptyErr::default()))
+            .map(Cmd::Import);
+        let mermaid = just("!mermaid ")
+            .ignore_then(Expr::parser())
+            .padded()
+            .map(Cmd::Mermaid);
+        let plantuml = just("!plantuml ")
+            .ignore_then(Expr::parser())
+            .padded()
+            .map(Cmd::PlantUml);
+
+        let algo_name = choice((
+            just("!components ").to(Algo::Components),
+            just("!degree ").to(Algo::Degree),
+            just("!bipartite ").to(Algo::Bipartite),
+            just("!cycles ").to(Algo::Cycles),
+            just("!color ").to(Algo::Color),
+        ));
+        let algo = algo_name
+            .then(Expr::parser())
+            .padded()
+            .map(|(a, e)| Cmd::Algo(a, e));
+        let path_algo = just("!path ")
+            .ignore_then(Expr::parser())
+            .then(Node::parser())
+            .then(Node::parser())
+            .padded()
+            .map(|((e, a), b)| Cmd::Algo(Algo::Path(a, b), e));
+
+        choice((
+            env, norm, limit, viz, dot, import, mermaid, plantuml, algo, path_algo,
+        ))
     }
 }
 
+#[derive(Clone)]
+enum NormOption {
+    Form(Form),
+    Duplicates,
+    Sorting,
+}
+
+#[derive(Clone)]
+enum LimitOption {
+    None,
+    Nodes(usize),
+    Cliques(usize),
+    Depth(usize),
+    Steps(usize),
+    Time(usize),
+}
+
 fn repl_parser<'src>() -> impl Parser<'src, &'src str, Input> {
     let stmt = Stmt::parser().map(|s| Input::Stmt(s));
     let expr = Expr::parser().map(|e| Input::Expr(e));
@@ -100,15 +224,35 @@ fn repl_parser<'src>() -> impl Parser<'src, &'src str, Input> {
     choice((stmt, expr, cmd))
 }
 
-fn handle_line<'cfg, 'src>(line: String, env: &mut Env<'cfg>, rl: &mut Editor<(), FileHistory>) {
+fn resolve_config(limits: &Limits) -> Config {
+    Config::default()
+        .with_shadowing()
+        .with_limits(limits.clone())
+}
+
+fn env_bindings(env: &Env) -> Vec<(Node, Expr)> {
+    env.bindings()
+        .map(|(node, expr)| (node.clone(), expr.clone()))
+        .collect()
+}
+
+// Returns the new limits when they're changed with `!limit`.
+fn handle_line<'cfg, 'src>(
+    line: String,
+    env: &mut Env<'cfg>,
+    norm: &mut normal::Config,
+    limits: &Limits,
+    rl: &mut Editor<(), FileHistory>,
+) -> Option<Limits> {
     match repl_parser().parse(&line).into_result() {
         Ok(input) => {
             rl.add_history_entry(&line).unwrap();
             match input {
-                Input::Expr(expr) => match expr.resolve(env) {
-                    Ok(expr) => println!("{}", expr.normalize()),
-                    Err(err) => println!("Error: {:?}", err),
-                },
+                Input::Expr(expr) => {
+                    if let Some(expr) = resolve_normal(&expr, env, norm) {
+                        println!("{}", expr);
+                    }
+                }
                 Input::Stmt(stmts) => {
                     if let Err(err) = stmts.resolve(env) {
                         println!("Error: {:?}", err);
@@ -117,15 +261,60 @@ fn handle_line<'cfg, 'src>(line: String, env: &mut Env<'cfg>, rl: &mut Editor<()
                 Input::Cmd(Cmd::Env) => {
                     print!("{}", env);
                 }
-                #[cfg(feature = "petgraph")]
-                Input::Cmd(Cmd::Viz(expr, save)) => match expr.resolve(env) {
-                    Ok(resolved) => {
-                        handle_viz(&resolved, save);
-                    }
-                    Err(err) => {
-                        println!("Error: {:?}", err);
+                Input::Cmd(Cmd::Normalize(None)) => {
+                    println!("{}", norm);
+                }
+                Input::Cmd(Cmd::Normalize(Some(config))) => {
+                    *norm = config.with_limits(limits.clone());
+                }
+                Input::Cmd(Cmd::Limit(options)) if options.is_empty() => {
+                    println!("{}", limits);
+                }
+                Input::Cmd(Cmd::Limit(options)) => {
+                    // Each option changes the current limits, and `none` clears
+                    // all of them, so `!limit none nodes 10` sets just one.
+                    let fresh = options.into_iter().fold(limits.clone(), |l, o| match o {
+                        LimitOption::None => Limits::default(),
+                        LimitOption::Nodes(n) => l.with_nodes(n),
+                        LimitOption::Cliques(n) => l.with_cliques(n),
+                        LimitOption::Depth(n) => l.with_depth(n),
+                        LimitOption::Steps(n) => l.with_steps(n),
+                        LimitOption::Time(n) => l.with_timeout(Duration::from_secs(n as u64)),
+                    });
+                    println!("{}", fresh);
+                    return Some(fresh);
+                }
+                Input::Cmd(Cmd::Viz(expr, save)) => {
+                    handle_viz(&expr, env, norm, save);
+                }
+                Input::Cmd(Cmd::Dot(expr)) => {
+                    match expr.to_dot_in(env, &dot::Config::default().with_clusters()) {
+                        Ok(dot) => print!("{}", dot),
+                        Err(err) => println!("Error: {:?}", err),
                     }
+                }
+                Input::Cmd(Cmd::Import(path)) => match fs::read_to_string(&path) {
+                    Ok(dot) => match Expr::from_dot(&dot) {
+                        Ok(expr) => println!("{}", expr),
+                        Err(_) => println!("Error: Invalid DOT in {}", path.display()),
+                    },
+                    Err(_) => println!("Failed to read {}", path.display()),
                 },
+                Input::Cmd(Cmd::Mermaid(expr)) => {
+                    if let Some(expr) = resolve_normal(&expr, env, norm) {
+                        print!("{}", expr.to_mermaid());
+                    }
+                }
+                Input::Cmd(Cmd::PlantUml(expr)) => {
+                    if let Some(expr) = resolve_normal(&expr, env, norm) {
+                        print!("{}", expr.to_plantuml());
+                    }
+                }
+                Input::Cmd(Cmd::Algo(algo, expr)) => {
+                    if let Some(expr) = resolve_normal(&expr, env, norm) {
+                        handle_algo(algo, &expr);
+                    }
+                }
             }
         }
         Err(_errors) => {
@@ -135,28 +324,101 @@ fn handle_line<'cfg, 'src>(line: String, env: &mut Env<'cfg>, rl: &mut Editor<()
             // }
         }
     }
+    None
 }
 
-#[cfg(feature = "petgraph")]
-fn handle_viz(expr: &Expr, save: Option<PathBuf>) {
-    let graph: Graph<Node, ()> = expr.into();
-    let dot = Dot::with_attr_getters(
-        &graph,
-        &[DotConfig::NodeNoLabel, DotConfig::EdgeNoLabel],
-        &|_, _| "".into(),
-        &|_, (_, node)| format!("label = \"{}\"", node),
-    );
+// Resolves and normalizes the expression within the limits, so every command
+// fails before doing unbounded work.
+fn resolve_normal(expr: &Expr, env: &mut Env, norm: &normal::Config) -> Option<Expr> {
+    match expr.resolve(env) {
+        Ok(expr) => match expr.try_normalize_with(norm) {
+            Ok(expr) => Some(expr),
+            Err(err) => {
+                println!("Error: {:?}", err);
+                None
+            }
+        },
+        Err(err) => {
+            println!("Error: {:?}", err);
+            None
+        }
+    }
+}
+
+fn handle_viz(expr: &Expr, env: &mut Env, norm: &normal::Config, save: Option<PathBuf>) {
     if let Some(path) = save {
-        if let Ok(mut file) = File::create(&path) {
-            if file.write_all(format!("{:?}", dot).as_bytes()).is_err() {
-                print!("Failed to write to {}", path.display());
+        let output = if path.extension().is_some_and(|ext| ext == "svg") {
+            match resolve_normal(expr, env, norm) {
+                Some(resolved) => resolved.to_svg(),
+                None => return,
+            }
+        } else {
+            match expr.to_dot_in(env, &dot::Config::default().with_clusters()) {
+                Ok(dot) => dot,
+                Err(err) => {
+                    println!("Error: {:?}", err);
+                    return;
+                }
             }
+        };
+        if fs::write(&path, output).is_err() {
+            println!("Failed to write to {}", path.display());
         }
-    } else {
-        print!("{:?}", dot);
+    } else if let Some(resolved) = resolve_normal(expr, env, norm) {
+        print!("{}", resolved.to_table());
     }
 }
 
+#[cfg(feature = "petgraph")]
+fn handle_algo(algo: Algo, expr: &Expr) {
+    // Groups of nodes print as disconnected nodes.
+    let group = |nodes: Vec<Node>| Expr::Disconnected(nodes.into_iter().map(Expr::Node).collect());
+    let literal = |nodes: Vec<Node>| nodes.into_iter().map(Expr::Node).collect();
+    match algo {
+        Algo::Components => println!("{}", Expr::Disconnected(expr.components())),
+        Algo::Path(a, b) => match expr.shortest_path(&a, &b) {
+            Some(path) => println!("{}", Expr::Path(literal(path))),
+            None => println!("No path from {} to {}", a, b),
+        },
+        Algo::Degree => {
+            // The group at each position has the nodes of that degree, e.g.
+            // `[[], [A, C], [B]]` for `(A, B, C)`.
+            let degrees = expr.degrees();
+            let max = degrees.values().copied().max().unwrap_or(0);
+            let mut groups = vec![vec![]; max + 1];
+            for (node, degree) in degrees {
+                groups[degree].push(node);
+            }
+            let groups = groups.into_iter().map(group).collect();
+            println!("{}", Expr::Disconnected(groups));
+        }
+        Algo::Bipartite => match expr.bipartition() {
+            Some((left, right)) => {
+                println!("{}", Expr::Disconnected(vec![group(left), group(right)]))
+            }
+            None => println!("Not bipartite"),
+        },
+        Algo::Cycles => {
+            let cycles = expr.cycles();
+            if cycles.is_empty() {
+                println!("No cycles");
+            }
+            for cycle in cycles {
+                println!("{}", Expr::Cycle(literal(cycle)));
+            }
+        }
+        Algo::Color => {
+            let classes = expr.coloring().into_iter().map(group).collect();
+            println!("{}", Expr::Disconnected(classes));
+        }
+    }
+}
+
+#[cfg(not(feature = "petgraph"))]
+fn handle_algo(_: Algo, _: &Expr) {
+    println!("Error: Graph algorithms need the petgraph feature");
+}
+
 const HISTDIR: &'static str = "grapl";
 const HISTFILE: &'static str = "grapl.history";
 
@@ -190,3 +452,61 @@ fn get_xdg_state_dir() -> Result<PathBuf, XdgError> {
     let state_dir = xdg.state()?;
     Ok(state_dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{env_bindings, handle_line, resolve_config};
+    use grapl::limit::{Limit, Limits};
+    use grapl::normal;
+    use grapl::resolve::{Env, Error};
+    use grapl::{Expr, Parse, Resolve, Stmt};
+    use pretty_assertions::assert_eq;
+    use rustyline::DefaultEditor;
+    use std::time::Duration;
+
+    #[test]
+    fn limit_resolution() {
+        let mut rl = DefaultEditor::new().unwrap();
+        let limits = Limits::default().with_timeout(Duration::from_secs(10));
+        let config = resolve_config(&limits);
+        let mut env = Env::new(&config);
+        let mut norm = normal::Config::default();
+        Vec::<Stmt>::parse("G1 = {A, B} G2 = {G1, G1} G3 = {G2, G2}")
+            .unwrap()
+            .resolve(&mut env)
+            .unwrap();
+
+        let fresh = handle_line(
+            "!limit nodes 4".into(),
+            &mut env,
+            &mut norm,
+            &limits,
+            &mut rl,
+        );
+        // Changes keep the other limits.
+        assert_eq!(fresh, Some(limits.clone().with_nodes(4)));
+
+        let config = resolve_config(&fresh.unwrap());
+        let mut env = Env::from_bindings(&config, env_bindings(&env)).unwrap();
+        assert_eq!(
+            Expr::parse("G2").unwrap().resolve(&mut env),
+            Ok(Expr::parse("{{A, B}, {A, B}}").unwrap())
+        );
+        assert_eq!(
+            Expr::parse("G3").unwrap().resolve(&mut env),
+            Err(Error::LimitExceeded(Limit::Nodes))
+        );
+
+        // `none` clears every limit before the options after it.
+        let fresh = handle_line("!limit none".into(), &mut env, &mut norm, &limits, &mut rl);
+        assert_eq!(fresh, Some(Limits::default()));
+        let fresh = handle_line(
+            "!limit none steps 7".into(),
+            &mut env,
+            &mut norm,
+            &limits,
+            &mut rl,
+        );
+        assert_eq!(fresh, Some(Limits::default().with_steps(7)));
+    }
+}
@@ -0,0 +1,92 @@
+//! Mermaid flowchart output.
+//!
+//! ```grapl
+//! {X, [A, B]}
+//! => to_mermaid
+//! graph LR
+//!     A
+//!     B
+//!     X
+//!     A --- X
+//!     B --- X
+//! ```
+
+use crate::graph::alias;
+use crate::{Expr, Graph, Node, is_identifier};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl Expr {
+    /// Returns the Mermaid flowchart for this expression's graph.
+    ///
+    /// Nodes which aren't plain identifiers get a generated id and are
+    /// labeled with their name.
+    pub fn to_mermaid(&self) -> String {
+        let graph = Graph::from(self);
+        let mut ids = BTreeMap::new();
+        let mut out = String::from("graph LR\n");
+        for (i, node) in graph.nodes().enumerate() {
+            if plain(node) {
+                writeln!(out, "    {}", node).unwrap();
+                ids.insert(node, node.to_string());
+            } else {
+                let id = alias(&graph, 'n', i);
+                writeln!(out, "    {}[\"{}\"]", id, label(node)).unwrap();
+                ids.insert(node, id);
+            }
+        }
+        for (a, b) in graph.edges() {
+            writeln!(out, "    {} --- {}", ids[a], ids[b]).unwrap();
+        }
+        out
+    }
+}
+
+// Mermaid ids are alphanumeric, and `end` closes subgraphs in any case.
+fn plain(node: &Node) -> bool {
+    is_identifier(node.as_str()) && !node.as_str().eq_ignore_ascii_case("end")
+}
+
+// Quotes inside labels are written as entity codes.
+fn label(node: &Node) -> String {
+    node.as_str().replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Graph, Node};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_mermaid() {
+        let graph: Graph = [
+            (Node::from("A"), Node::from("end")),
+            (Node::from("A"), Node::from("say \"hi\"")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            Expr::from(&graph).to_mermaid(),
+            "graph LR
+    A
+    n1[\"end\"]
+    n2[\"say #quot;hi#quot;\"]
+    A --- n1
+    A --- n2
+"
+        );
+
+        // Generated ids skip the names of other nodes.
+        let graph: Graph = [(Node::from("a b"), Node::from("n0"))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            Expr::from(&graph).to_mermaid(),
+            "graph LR
+    n0_[\"a b\"]
+    n0
+    n0_ --- n0
+"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Multigraphs, which count repeated edges and self-loops.
+//!
+//! A [`Graph`](crate::Graph) has at most one edge between two nodes and
+//! ignores self-loops. A [`Multigraph`] is the opt-in alternative which keeps
+//! them: every pair of entries in a clique of the expression is an edge, so a
+//! repeated node is a self-loop and a repeated clique is a parallel edge.
+//!
+//! ```grapl
+//! [{A, A}, {A, B}, (A, B)]
+//! => A-A x1, A-B x2
+//! ```
+//!
+//! With the `petgraph` feature, a multigraph converts into petgraph graphs with
+//! parallel edges.
+
+use crate::normal::Config;
+use crate::{Expr, Node, Normalize};
+use std::collections::BTreeMap;
+
+/// An undirected graph with counted edges, stored as adjacency maps.
+///
+/// ```
+/// use grapl::{Expr, Multigraph, Node, Parse};
+///
+/// let graph = Multigraph::from(&Expr::parse("[{A, A}, {A, B}, {A, B}]").unwrap());
+/// assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("A")), 1);
+/// assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("B")), 2);
+/// assert_eq!(graph.edge_count(), 3);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Multigraph(BTreeMap<Node, BTreeMap<Node, usize>>);
+
+impl Multigraph {
+    /// Create a new multigraph without any nodes.
+    pub fn new() -> Self {
+        Multigraph(BTreeMap::new())
+    }
+
+    /// Adds a node without any edges, if it isn't already in this graph.
+    pub fn add_node(&mut self, node: Node) {
+        self.0.entry(node).or_default();
+    }
+
+    /// Adds another edge between two nodes, adding the nodes as needed.
+    ///
+    /// An edge from a node to itself is a self-loop.
+    pub fn add_edge(&mut self, a: Node, b: Node) {
+        *self
+            .0
+            .entry(a.clone())
+            .or_default()
+            .entry(b.clone())
+            .or_default() += 1;
+        if a != b {
+            *self.0.entry(b).or_default().entry(a).or_default() += 1;
+        }
+    }
+
+    /// Returns true if the given node is in this graph.
+    pub fn has_node(&self, node: &Node) -> bool {
+        self.0.contains_key(node)
+    }
+
+    /// Returns the number of edges between the given nodes.
+    pub fn multiplicity(&self, a: &Node, b: &Node) -> usize {
+        self.0
+            .get(a)
+            .and_then(|adjacent| adjacent.get(b))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of edge ends at the given node, so a self-loop
+    /// counts twice.
+    pub fn degree(&self, node: &Node) -> usize {
+        self.0.get(node).map_or(0, |adjacent| {
+            adjacent
+                .iter()
+                .map(|(other, n)| if other == node { 2 * n } else { *n })
+                .sum()
+        })
+    }
+
+    /// Returns the **sorted** nodes of this graph.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.0.keys()
+    }
+
+    /// Returns the **sorted** edges of this graph with their multiplicities,
+    /// with each undirected edge only once as `(a, b, n)` where `a <= b`.
+    pub fn edges(&self) -> impl Iterator<Item = (&Node, &Node, usize)> {
+        self.0
+            .iter()
+            .flat_map(|(a, adjacent)| adjacent.range::<Node, _>(a..).map(move |(b, n)| (a, b, *n)))
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the number of edges, counting each parallel edge.
+    pub fn edge_count(&self) -> usize {
+        self.edges().map(|(_, _, n)| n).sum()
+    }
+}
+
+/// Counts the edges of the expression's cliques, keeping duplicates.
+///
+/// ```grapl
+/// {A, [B, B]}
+/// => [{A, B}, {A, B}]
+/// => A-B x2
+/// ```
+impl From<&Expr> for Multigraph {
+    fn from(expr: &Expr) -> Self {
+        let mut graph = Multigraph::new();
+        let expr = expr.normalize_with(&Config::default().with_duplicates());
+        let cliques = match &expr {
+            Expr::Disconnected(exprs) => exprs.as_slice(),
+            e => std::slice::from_ref(e),
+        };
+        for clique in cliques {
+            let nodes = match clique {
+                Expr::Node(node) => vec![node.clone()],
+                // With duplicates, cliques keep every repeated node.
+                Expr::Connected(cs) => cs.iter().flat_map(Expr::nodes).collect(),
+                _ => unreachable!(),
+            };
+            for (i, a) in nodes.iter().enumerate() {
+                graph.add_node(a.clone());
+                for b in &nodes[i + 1..] {
+                    graph.add_edge(a.clone(), b.clone());
+                }
+            }
+        }
+        graph
+    }
+}
+
+/// Rebuilds an expression with a clique for each edge, repeated for parallel
+/// edges.
+///
+/// ```grapl
+/// A-A x1, A-B x2, C
+/// => [{A, A}, {A, B}, {A, B}, C]
+/// ```
+impl From<&Multigraph> for Expr {
+    fn from(graph: &Multigraph) -> Self {
+        let mut ds = vec![];
+        for (a, b, n) in graph.edges() {
+            let edge = Expr::Connected(vec![Expr::Node(a.clone()), Expr::Node(b.clone())]);
+            ds.extend(std::iter::repeat_n(edge, n));
+        }
+        ds.extend(
+            graph
+                .nodes()
+                .filter(|node| graph.degree(node) == 0)
+                .map(|node| Expr::Node(node.clone())),
+        );
+        Expr::Disconnected(ds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Multigraph;
+    use crate::{Expr, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    fn multigraph(s: &str) -> Multigraph {
+        Multigraph::from(&Expr::parse(s).unwrap())
+    }
+
+    #[test]
+    fn self_loops() {
+        let graph = multigraph("{A, A}");
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("A")), 1);
+        assert_eq!(graph.degree(&Node::from("A")), 2);
+
+        let graph = multigraph("{A, [B, A]}");
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![
+                (&Node::from("A"), &Node::from("A"), 1),
+                (&Node::from("A"), &Node::from("B"), 1)
+            ]
+        );
+        assert_eq!(graph.degree(&Node::from("A")), 3);
+    }
+
+    #[test]
+    fn parallel_edges() {
+        let graph = multigraph("[{A, B}, {A, B, C}, (A, B), D]");
+        assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("B")), 3);
+        assert_eq!(graph.multiplicity(&Node::from("B"), &Node::from("A")), 3);
+        assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("C")), 1);
+        assert_eq!(graph.multiplicity(&Node::from("A"), &Node::from("D")), 0);
+        assert_eq!(graph.edge_count(), 5);
+        assert!(graph.has_node(&Node::from("D")));
+
+        // A path back along the same edge walks it twice.
+        assert_eq!(multigraph("(A, B, A)").edge_count(), 2);
+        assert_eq!(multigraph("{A, [B, B]}").edge_count(), 2);
+    }
+
+    #[test]
+    fn multigraph_expr() {
+        let graph = multigraph("[{A, A}, {A, B}, {B, A}, C]");
+        let expr = Expr::from(&graph);
+        assert_eq!(expr.to_string(), "[{A, A}, {A, B}, {A, B}, C]");
+        assert_eq!(Multigraph::from(&expr), graph);
+        assert_eq!(Expr::from(&Multigraph::new()), Expr::parse("[]").unwrap());
+    }
+}
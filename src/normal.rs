@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 
-use crate::{Expr, Ret, Stmt};
+use crate::graph::{self, Graph};
+use crate::limit::{Budget, LimitExceeded, Limits};
+use crate::{Expr, Node, Ret, Stmt};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// Reductions to normal form.
 ///
@@ -13,13 +17,161 @@ use crate::{Expr, Ret, Stmt};
 /// {[A, B], [C, D]} =>
 /// [{A, C}, {A, D}, {B, C}, {B, D}]
 /// ```
+/// - Paths and cycles are replaced by the connections between consecutive
+///   expressions:
+/// ```grapl
+/// (A, B, C)+ =>
+/// [{A, B}, {B, C}, {C, A}]
+/// ```
+/// - Products are replaced by the cliques of their graph:
+/// ```grapl
+/// {A, B} & {X, Y} =>
+/// [{"(A, X)", "(B, Y)"}, {"(A, Y)", "(B, X)"}]
+/// ```
+/// - Subgraphs are replaced by the cliques left after removing nodes:
+/// ```grapl
+/// {X, [A, B]} \ X =>
+/// [A, B]
+/// ```
+/// - Comprehensions are replaced by their expansion:
+/// ```grapl
+/// [{x, y} for x in [A, B], y in [C]] =>
+/// [{A, C}, {B, C}]
+/// ```
+///
+/// See [`Config`] for the options which change the shape of the output.
 pub trait Normalize: Sized {
-    fn normalize(&self) -> Self;
+    /// Normalize within the [`Limits`] of the given configuration.
+    fn try_normalize_with(&self, config: &Config) -> Result<Self, LimitExceeded>;
+
+    /// Normalize with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration's [`Limits`] are exceeded, use
+    /// [`Normalize::try_normalize_with`] to handle this case.
+    fn normalize_with(&self, config: &Config) -> Self {
+        self.try_normalize_with(config)
+            .expect("normalization limit exceeded")
+    }
+
+    fn normalize(&self) -> Self {
+        self.normalize_with(&Config::default())
+    }
 }
 
-impl Expr {
-    fn flatten(&self) -> Self {
+/// Graph normalization configuration options.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    form: Form,
+    duplicates: bool,
+    sorting: bool,
+    limits: Limits,
+}
+
+impl Config {
+    /// Select the shape of normalized expressions.
+    pub fn with_form(mut self, form: Form) -> Self {
+        self.form = form;
+        self
+    }
+
+    /// Keep duplicate nodes and subsumed cliques in the output.
+    ///
+    /// ```grapl
+    /// [{A, B}, {A, B, C}, A]
+    /// => [{A, B}, {A, B, C}, A]
+    /// ```
+    pub fn with_duplicates(mut self) -> Self {
+        self.duplicates = true;
+        self
+    }
+
+    /// Sort nodes and sub-expressions in the output.
+    ///
+    /// ```grapl
+    /// [{C, A}, B]
+    /// => [{A, C}, B]
+    /// ```
+    pub fn with_sorting(mut self) -> Self {
+        self.sorting = true;
+        self
+    }
+
+    /// Bound the work done during normalization.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl std::fmt::Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.form)?;
+        if self.duplicates {
+            write!(f, " dups")?;
+        }
+        if self.sorting {
+            write!(f, " sort")?;
+        }
+        Ok(())
+    }
+}
+
+/// The shape of a normalized expression.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Form {
+    /// A fully disconnected expression of cliques.
+    ///
+    /// ```grapl
+    /// {A, [B, C]}
+    /// => [{A, B}, {A, C}]
+    /// ```
+    #[default]
+    Dnf,
+    /// Cliques sharing nodes are factored back into connected expressions.
+    ///
+    /// ```grapl
+    /// [{A, B}, {A, C}, {D, E}]
+    /// => [{A, [B, C]}, {D, E}]
+    /// ```
+    Factored,
+}
+
+impl std::fmt::Display for Form {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Form::Dnf => write!(f, "dnf"),
+            Form::Factored => write!(f, "factored"),
+        }
+    }
+}
+
+impl Expr {
+    // Flatten (and dedup) without any of the final presentation steps, since
+    // `flatten` relies on the DNF shape of its normalized sub-expressions.
+    fn reduce(
+        &self,
+        config: &Config,
+        budget: &Budget,
+        depth: usize,
+    ) -> Result<Self, LimitExceeded> {
+        let mut expr = self.flatten(config, budget, depth)?;
+        if !config.duplicates {
+            expr = expr.dedup();
+        }
+        expr.flatten(config, budget, depth)
+    }
+
+    fn flatten(
+        &self,
+        config: &Config,
+        budget: &Budget,
+        depth: usize,
+    ) -> Result<Self, LimitExceeded> {
+        budget.step()?;
+        budget.depth(depth)?;
+        Ok(match self {
             Expr::Node(node) => Expr::Node(node.clone()),
             Expr::Connected(exprs) => {
                 // General reduction strategy follow these steps:
@@ -31,18 +183,21 @@ impl Expr {
 
                 // Collect a list of disconnected connected nodes.
                 let mut dcs = vec![];
-                for expr in exprs {
+                let mut nodes: usize = 0;
+                for expr in reduce_all(exprs, config, budget, depth + 1)? {
                     // dcs = []
                     // dcs <= [[]]
                     if dcs.is_empty() {
                         dcs.push(vec![]);
                     }
 
-                    match expr.normalize() {
+                    match expr {
                         // dcs = [[A],[B]]
                         // expr = C
                         // dcs <= [[A,C],[B,C]]
                         e @ Expr::Node(_) => {
+                            nodes = nodes.saturating_add(dcs.len());
+                            budget.nodes(nodes)?;
                             for dc in dcs.iter_mut() {
                                 dc.push(e.clone());
                             }
@@ -51,6 +206,8 @@ impl Expr {
                         // expr = {C,D}
                         // dcs <= [[A,C,D],[B,C,D]]
                         Expr::Connected(cexprs) => {
+                            nodes = nodes.saturating_add(dcs.len().saturating_mul(cexprs.len()));
+                            budget.nodes(nodes)?;
                             for cexpr in cexprs {
                                 for dc in dcs.iter_mut() {
                                     dc.push(cexpr.clone());
@@ -60,30 +217,23 @@ impl Expr {
                         // dcs = [[A,B][C]]
                         // expr = [D,E]
                         // dcs <= [[A,B,D],[C,D],[A,B,E],[C,E]]
+                        // Paths, cycles, products, subgraphs and
+                        // comprehensions are reduced to their graph.
+                        Expr::Path(_)
+                        | Expr::Cycle(_)
+                        | Expr::Product(..)
+                        | Expr::Subgraph(..)
+                        | Expr::Comprehension(_) => unreachable!(),
                         Expr::Disconnected(dexprs) => {
-                            let mut freshs = vec![];
-                            for dc in dcs.iter() {
-                                for dexpr in dexprs.iter() {
-                                    let mut fresh = dc.clone();
-                                    match dexpr {
-                                        // This is kinda gnarly... but we need
-                                        // to flatten connected expressions
-                                        // inside disconnected expression. E.g:
-                                        // {A,[{B,C},D]}.
-                                        e @ Expr::Node(_) => fresh.push(e.clone()),
-                                        Expr::Connected(cs) => {
-                                            for c in cs {
-                                                fresh.push(c.clone());
-                                            }
-                                        }
-                                        // This subexpression is normalized and
-                                        // therefore cannot have nested [[]].
-                                        Expr::Disconnected(_) => unreachable!(),
-                                    }
-                                    freshs.push(fresh.clone());
-                                }
-                            }
-                            dcs = freshs;
+                            // Check the size of the product before building it.
+                            budget.cliques(dcs.len().saturating_mul(dexprs.len()))?;
+                            let dexpr_nodes: usize = dexprs.iter().map(Expr::size).sum();
+                            nodes = nodes
+                                .saturating_mul(dexprs.len())
+                                .saturating_add(dcs.len().saturating_mul(dexpr_nodes));
+                            budget.nodes(nodes)?;
+
+                            dcs = expand(&dcs, &dexprs);
                         }
                     }
                 }
@@ -104,8 +254,8 @@ impl Expr {
             Expr::Disconnected(exprs) => {
                 // Collect a list of disconnected nodes.
                 let mut ds = vec![];
-                for expr in exprs {
-                    match expr.normalize() {
+                for expr in reduce_all(exprs, config, budget, depth + 1)? {
+                    match expr {
                         // ds = [A,B]
                         // expr = {C,D}
                         // ds <= [A,B,{C,D}]
@@ -118,8 +268,17 @@ impl Expr {
                                 ds.push(dexpr);
                             }
                         }
+                        // Paths, cycles, products, subgraphs and
+                        // comprehensions are reduced to their graph.
+                        Expr::Path(_)
+                        | Expr::Cycle(_)
+                        | Expr::Product(..)
+                        | Expr::Subgraph(..)
+                        | Expr::Comprehension(_) => unreachable!(),
                     }
                 }
+                budget.cliques(ds.len())?;
+                budget.nodes(ds.iter().map(Expr::size).sum())?;
 
                 if ds.len() == 1 {
                     // [A] => A
@@ -129,6 +288,54 @@ impl Expr {
                     Expr::Disconnected(ds)
                 }
             }
+            // (A, B, C)+ => [{A, B}, {B, C}, {C, A}]
+            Expr::Path(exprs) => links(exprs, false).flatten(config, budget, depth)?,
+            Expr::Cycle(exprs) => links(exprs, true).flatten(config, budget, depth)?,
+            Expr::Product(product, a, b) => {
+                let a = Graph::from(&a.reduce(config, budget, depth + 1)?);
+                let b = Graph::from(&b.reduce(config, budget, depth + 1)?);
+                budget.nodes(a.node_count().saturating_mul(b.node_count()))?;
+                let graph = product.apply(&a, &b);
+
+                // {A, B} * C => [{(A, C), (B, C)}]
+                let mut ds = graph::cliques(&graph);
+                budget.cliques(ds.len())?;
+
+                if ds.len() == 1 {
+                    ds.remove(0)
+                } else {
+                    Expr::Disconnected(ds)
+                }
+            }
+            // {X, [A, B]} \ X => [A, B]
+            Expr::Subgraph(subgraph, a, b) => {
+                let a = a.reduce(config, budget, depth + 1)?;
+                let b = b.reduce(config, budget, depth + 1)?;
+                let mut ds = subgraph.apply(&a, &b);
+                budget.cliques(ds.len())?;
+
+                if ds.len() == 1 {
+                    ds.remove(0)
+                } else {
+                    Expr::Disconnected(ds)
+                }
+            }
+            // [x for x in [A, B]] => [A, B]
+            Expr::Comprehension(c) => c.expand(budget)?.flatten(config, budget, depth)?,
+        })
+    }
+
+    // The number of nodes, counting repeats, in this expression.
+    pub(crate) fn size(&self) -> usize {
+        match self {
+            Expr::Node(_) => 1,
+            Expr::Connected(exprs)
+            | Expr::Disconnected(exprs)
+            | Expr::Path(exprs)
+            | Expr::Cycle(exprs) => exprs.iter().map(Expr::size).sum(),
+            Expr::Product(_, a, b) => a.size().saturating_mul(b.size()),
+            Expr::Subgraph(_, a, _) => a.size(),
+            Expr::Comprehension(c) => c.size(),
         }
     }
 
@@ -147,6 +354,7 @@ impl Expr {
                     for f in fresh.iter() {
                         if expr.is_norm_subgraph(&f) {
                             action = Action::Skip;
+                            break;
                         } else if f.is_norm_subgraph(expr) {
                             action = Action::Swap;
                         }
@@ -156,7 +364,9 @@ impl Expr {
                             fresh.push(expr.clone());
                         }
                         Action::Swap => {
-                            fresh.remove(fresh.len() - 1);
+                            // Drop every clique the new one subsumes, not
+                            // just the last one inserted.
+                            fresh.retain(|f: &Expr| !f.is_norm_subgraph(expr));
                             fresh.push(expr.clone());
                         }
                         Action::Skip => {}
@@ -166,7 +376,12 @@ impl Expr {
             }};
         }
         match self {
-            e @ Expr::Node(_) => e.clone(),
+            e @ (Expr::Node(_)
+            | Expr::Path(_)
+            | Expr::Cycle(_)
+            | Expr::Product(..)
+            | Expr::Subgraph(..)
+            | Expr::Comprehension(_)) => e.clone(),
             Expr::Connected(exprs) => dedup_exprs!(Expr::Connected, exprs),
             Expr::Disconnected(exprs) => dedup_exprs!(Expr::Disconnected, exprs),
         }
@@ -176,33 +391,223 @@ impl Expr {
         let set: HashSet<_> = other.nodes().iter().cloned().collect();
         self.nodes().iter().all(|node| set.contains(node))
     }
+
+    // This only works on normalized expressions.
+    fn factor(&self) -> Self {
+        match self {
+            Expr::Disconnected(exprs) => {
+                let cliques = exprs
+                    .iter()
+                    .map(|e| match e {
+                        Expr::Node(node) => vec![node.clone()],
+                        Expr::Connected(cs) => cs.iter().flat_map(Expr::nodes).collect(),
+                        // This expression is normalized and therefore cannot
+                        // have nested [[]] or any of the other forms.
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                factor_cliques(cliques)
+            }
+            e => e.clone(),
+        }
+    }
+
+    fn sorted(&self) -> Self {
+        let sort = |exprs: &[Expr]| {
+            let mut exprs: Vec<_> = exprs.iter().map(Expr::sorted).collect();
+            exprs.sort_by_cached_key(|e| (e.nodes(), e.to_string()));
+            exprs
+        };
+        match self {
+            e @ (Expr::Node(_)
+            | Expr::Path(_)
+            | Expr::Cycle(_)
+            | Expr::Product(..)
+            | Expr::Subgraph(..)
+            | Expr::Comprehension(_)) => e.clone(),
+            Expr::Connected(exprs) => Expr::Connected(sort(exprs)),
+            Expr::Disconnected(exprs) => Expr::Disconnected(sort(exprs)),
+        }
+    }
+}
+
+// Reduce each of the sub-expressions, in parallel with the `rayon` feature.
+fn reduce_all(
+    exprs: &[Expr],
+    config: &Config,
+    budget: &Budget,
+    depth: usize,
+) -> Result<Vec<Expr>, LimitExceeded> {
+    #[cfg(feature = "rayon")]
+    let exprs = exprs.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let exprs = exprs.iter();
+    exprs.map(|e| e.reduce(config, budget, depth)).collect()
+}
+
+// Add each of the disconnected expressions to each of the connected lists, in
+// parallel with the `rayon` feature.
+//
+// dcs = [[A,B][C]]
+// dexprs = [D,E]
+// => [[A,B,D],[A,B,E],[C,D],[C,E]]
+fn expand(dcs: &[Vec<Expr>], dexprs: &[Expr]) -> Vec<Vec<Expr>> {
+    #[cfg(feature = "rayon")]
+    let dcs = dcs
+        .par_iter()
+        .flat_map_iter(|dc| dexprs.iter().map(move |d| extend(dc, d)));
+    #[cfg(not(feature = "rayon"))]
+    let dcs = dcs
+        .iter()
+        .flat_map(|dc| dexprs.iter().map(move |d| extend(dc, d)));
+    dcs.collect()
+}
+
+fn extend(dc: &[Expr], dexpr: &Expr) -> Vec<Expr> {
+    let mut fresh = dc.to_vec();
+    match dexpr {
+        // This is kinda gnarly... but we need to flatten connected expressions
+        // inside disconnected expression. E.g: {A,[{B,C},D]}.
+        e @ Expr::Node(_) => fresh.push(e.clone()),
+        Expr::Connected(cs) => fresh.extend(cs.iter().cloned()),
+        // This subexpression is normalized and therefore cannot have nested
+        // [[]], paths, cycles or products.
+        _ => unreachable!(),
+    }
+    fresh
+}
+
+// Connect each expression to the next one, and the last back to the first to
+// close a cycle. Cycles of one or two expressions are the same as paths.
+//
+// (A, B, C)+ => [{A, B}, {B, C}, {C, A}]
+fn links(exprs: &[Expr], cycle: bool) -> Expr {
+    match exprs {
+        [] => Expr::Disconnected(vec![]),
+        [expr] => expr.clone(),
+        _ => {
+            let mut links: Vec<_> = exprs
+                .windows(2)
+                .map(|pair| Expr::Connected(pair.to_vec()))
+                .collect();
+            if cycle && exprs.len() > 2 {
+                let last = exprs[exprs.len() - 1].clone();
+                links.push(Expr::Connected(vec![last, exprs[0].clone()]));
+            }
+            Expr::Disconnected(links)
+        }
+    }
+}
+
+// Factor the node shared by the most cliques out of them, recursively:
+// [{A, B}, {A, C}, {D, E}] =>
+// [{A, [B, C]}, {D, E}]
+fn factor_cliques(mut cliques: Vec<Vec<Node>>) -> Expr {
+    let clique = |mut nodes: Vec<Node>| {
+        if nodes.len() == 1 {
+            Expr::Node(nodes.remove(0))
+        } else {
+            Expr::Connected(nodes.into_iter().map(Expr::Node).collect())
+        }
+    };
+
+    if cliques.len() == 1 {
+        return clique(cliques.remove(0));
+    }
+
+    // Pick the first node which is shared by the most cliques.
+    let mut shared: Option<(&Node, usize)> = None;
+    for node in cliques.iter().flatten() {
+        let count = cliques.iter().filter(|c| c.contains(node)).count();
+        if count > 1 && shared.is_none_or(|(_, max)| count > max) {
+            shared = Some((node, count));
+        }
+    }
+    let Some((shared, _)) = shared else {
+        return Expr::Disconnected(cliques.into_iter().map(clique).collect());
+    };
+    let shared = shared.clone();
+
+    let (with, without): (Vec<_>, Vec<_>) = cliques.into_iter().partition(|c| c.contains(&shared));
+    let common: Vec<Node> = with[0]
+        .iter()
+        .filter(|n| with.iter().all(|c| c.contains(n)))
+        .cloned()
+        .collect();
+    let rest: Vec<Vec<Node>> = with
+        .into_iter()
+        .map(|c| {
+            c.into_iter()
+                .filter(|n| !common.contains(n))
+                .collect::<Vec<_>>()
+        })
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    let mut factored: Vec<Expr> = common.into_iter().map(Expr::Node).collect();
+    if !rest.is_empty() {
+        match factor_cliques(rest) {
+            Expr::Connected(exprs) => factored.extend(exprs),
+            e => factored.push(e),
+        }
+    }
+    let factored = if factored.len() == 1 {
+        factored.remove(0)
+    } else {
+        Expr::Connected(factored)
+    };
+
+    if without.is_empty() {
+        factored
+    } else {
+        let mut ds = vec![factored];
+        match factor_cliques(without) {
+            Expr::Disconnected(exprs) => ds.extend(exprs),
+            e => ds.push(e),
+        }
+        Expr::Disconnected(ds)
+    }
 }
 
 impl Normalize for Expr {
-    fn normalize(&self) -> Self {
-        self.flatten().dedup().flatten()
+    fn try_normalize_with(&self, config: &Config) -> Result<Self, LimitExceeded> {
+        let budget = Budget::new(&config.limits);
+        let mut expr = self.reduce(config, &budget, 0)?;
+        if config.form == Form::Factored {
+            expr = expr.factor();
+        }
+        if config.sorting {
+            expr = expr.sorted();
+        }
+        Ok(expr)
     }
 }
 
 impl<'src> Normalize for Stmt {
-    fn normalize(&self) -> Self {
+    fn try_normalize_with(&self, config: &Config) -> Result<Self, LimitExceeded> {
         match self {
-            Stmt::Assign(node, expr) => Stmt::Assign(node.clone(), expr.normalize()),
+            Stmt::Assign(node, expr) => {
+                Ok(Stmt::Assign(node.clone(), expr.try_normalize_with(config)?))
+            }
         }
     }
 }
 
 impl Normalize for Ret {
-    fn normalize(&self) -> Self {
-        let norm_stmts = self.0.iter().map(Normalize::normalize).collect();
-        let norm_expr = self.1.normalize();
-        Ret(norm_stmts, norm_expr)
+    fn try_normalize_with(&self, config: &Config) -> Result<Self, LimitExceeded> {
+        let mut norm_stmts = vec![];
+        for stmt in &self.0 {
+            norm_stmts.push(stmt.try_normalize_with(config)?);
+        }
+        let norm_expr = self.1.try_normalize_with(config)?;
+        Ok(Ret(norm_stmts, norm_expr))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Normalize;
+    use super::{Config, Form, Normalize};
+    use crate::limit::{Limit, LimitExceeded, Limits};
     use crate::{Expr, Parse, Ret, Stmt};
     use chumsky::Parser;
     use pretty_assertions::assert_eq;
@@ -400,11 +805,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(
+            Expr::parse("(A, B, C, D)").unwrap().normalize(),
+            Expr::parse("[{A, B}, {B, C}, {C, D}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("(A, B, C, D)+").unwrap().normalize(),
+            Expr::parse("[{A, B}, {B, C}, {C, D}, {D, A}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("(A, [B, C], D)").unwrap().normalize(),
+            Expr::parse("[{A, B}, {A, C}, {B, D}, {C, D}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("(A, B)+").unwrap().normalize(),
+            Expr::parse("{A, B}").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("(A)+").unwrap().normalize(),
+            Expr::parse("A").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("()").unwrap().normalize(),
+            Expr::parse("[]").unwrap(),
+        );
+    }
+
     #[test]
     fn disconnected_dups() {
         assert_eq!(
             Expr::parse("[N,[I,{N,[J]}]]").unwrap().normalize(),
-            Expr::parse("[N,I,{N,J}]").unwrap(),
+            Expr::parse("[I,{N,J}]").unwrap(),
         );
         assert_eq!(
             Expr::parse("[N,I,{N,J}]").unwrap().dedup(),
@@ -412,6 +845,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dedup_subsumed() {
+        // A clique replaces the cliques it subsumes, wherever they are.
+        assert_eq!(
+            Expr::parse("[A, B, {A, C}]").unwrap().dedup(),
+            Expr::parse("[B, {A, C}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("[{A, B}, {C, D}, {A, B, C, D}]")
+                .unwrap()
+                .dedup(),
+            Expr::parse("[{A, B, C, D}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("[{A, B, C}, D, {A, B}]").unwrap().dedup(),
+            Expr::parse("[{A, B, C}, D]").unwrap(),
+        );
+    }
+
     #[test]
     fn normalize_stmts() {
         assert_eq!(
@@ -459,4 +911,85 @@ mod tests {
                 .unwrap(),
         );
     }
+
+    #[test]
+    fn normalize_factored() {
+        let config = Config::default().with_form(Form::Factored);
+        assert_eq!(
+            Expr::parse("{A, [B, C]}").unwrap().normalize_with(&config),
+            Expr::parse("{A, [B, C]}").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("[{A, B}, {A, C}, {D, E}]")
+                .unwrap()
+                .normalize_with(&config),
+            Expr::parse("[{A, [B, C]}, {D, E}]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("{A, B, [C, D], E}")
+                .unwrap()
+                .normalize_with(&config),
+            Expr::parse("{A, B, E, [C, D]}").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("[A, B]").unwrap().normalize_with(&config),
+            Expr::parse("[A, B]").unwrap(),
+        );
+    }
+
+    #[test]
+    fn normalize_duplicates() {
+        let config = Config::default().with_duplicates();
+        assert_eq!(
+            Expr::parse("[{A, B}, {A, B, C}, A]")
+                .unwrap()
+                .normalize_with(&config),
+            Expr::parse("[{A, B}, {A, B, C}, A]").unwrap(),
+        );
+        assert_eq!(
+            Expr::parse("{A, [B], A}").unwrap().normalize_with(&config),
+            Expr::parse("{A, B, A}").unwrap(),
+        );
+    }
+
+    #[test]
+    fn normalize_sorting() {
+        let config = Config::default().with_sorting();
+        assert_eq!(
+            Expr::parse("[{D, C}, B, {A, E}]")
+                .unwrap()
+                .normalize_with(&config),
+            Expr::parse("[{A, E}, B, {C, D}]").unwrap(),
+        );
+    }
+
+    #[test]
+    fn normalize_limits() {
+        let expr = Expr::parse("{[A, B], [C, D], [E, F], [G, H]}").unwrap();
+        let config = Config::default().with_limits(Limits::default().with_cliques(8));
+        assert_eq!(
+            expr.try_normalize_with(&config),
+            Err(LimitExceeded(Limit::Cliques)),
+        );
+        let config = Config::default().with_limits(Limits::default().with_cliques(16));
+        assert_eq!(expr.try_normalize_with(&config), Ok(expr.normalize()));
+
+        let config = Config::default().with_limits(Limits::default().with_nodes(32));
+        assert_eq!(
+            expr.try_normalize_with(&config),
+            Err(LimitExceeded(Limit::Nodes)),
+        );
+        let config = Config::default().with_limits(Limits::default().with_steps(4));
+        assert_eq!(
+            expr.try_normalize_with(&config),
+            Err(LimitExceeded(Limit::Steps)),
+        );
+        let config = Config::default().with_limits(Limits::default().with_depth(1));
+        assert_eq!(
+            Expr::parse("{A, [B, {C, D}]}")
+                .unwrap()
+                .try_normalize_with(&config),
+            Err(LimitExceeded(Limit::Depth)),
+        );
+    }
 }
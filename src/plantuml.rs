@@ -0,0 +1,100 @@
+//! PlantUML diagram output.
+//!
+//! ```grapl
+//! {X, [A, B]}
+//! => to_plantuml
+//! @startuml
+//! agent A
+//! agent B
+//! agent X
+//! A -- X
+//! B -- X
+//! @enduml
+//! ```
+
+use crate::graph::alias;
+use crate::{Expr, Graph, is_identifier};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl Expr {
+    /// Returns the PlantUML diagram for this expression's graph, with each
+    /// node drawn as an `agent`.
+    ///
+    /// Nodes which aren't plain identifiers get a generated alias.
+    pub fn to_plantuml(&self) -> String {
+        let graph = Graph::from(self);
+        let mut ids = BTreeMap::new();
+        let mut out = String::from("@startuml\n");
+        for (i, node) in graph.nodes().enumerate() {
+            if is_identifier(node.as_str()) {
+                writeln!(out, "agent {}", node).unwrap();
+                ids.insert(node, node.to_string());
+            } else {
+                // Display names can't contain quotes.
+                let id = alias(&graph, 'n', i);
+                writeln!(
+                    out,
+                    "agent \"{}\" as {}",
+                    node.as_str().replace('"', "'"),
+                    id
+                )
+                .unwrap();
+                ids.insert(node, id);
+            }
+        }
+        for (a, b) in graph.edges() {
+            writeln!(out, "{} -- {}", ids[a], ids[b]).unwrap();
+        }
+        out.push_str("@enduml\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Graph, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_plantuml() {
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_plantuml(),
+            "@startuml
+agent A
+agent B
+agent X
+A -- X
+B -- X
+@enduml
+"
+        );
+
+        let graph: Graph = [(Node::from("A"), Node::from("web 1"))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            Expr::from(&graph).to_plantuml(),
+            "@startuml
+agent A
+agent \"web 1\" as n1
+A -- n1
+@enduml
+"
+        );
+
+        // Generated aliases skip the names of other nodes.
+        let graph: Graph = [(Node::from("a b"), Node::from("n0"))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            Expr::from(&graph).to_plantuml(),
+            "@startuml
+agent \"a b\" as n0_
+agent n0
+n0_ -- n0
+@enduml
+"
+        );
+    }
+}
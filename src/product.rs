@@ -0,0 +1,178 @@
+//! Graph products, which combine two graphs into a graph on pairs of nodes.
+//!
+//! The product of `G` and `H` has a node `(A, X)` for each node `A` of `G`
+//! and `X` of `H`. Products are how grids, tori and hypercubes are written
+//! without listing every edge:
+//!
+//! ```grapl
+//! {A, B} * {X, Y}
+//! => [{"(A, X)", "(A, Y)"}, {"(A, X)", "(B, X)"}, {"(A, Y)", "(B, Y)"}, {"(B, X)", "(B, Y)"}]
+//! ```
+//!
+//! Components which aren't identifiers are quoted, so a product of products
+//! has nodes like `("(A, X)", P)`.
+
+use crate::{Expr, Graph, Node};
+use std::fmt;
+
+/// The ways two graphs can be multiplied.
+///
+/// Pairs `(A, X)` and `(B, Y)` are adjacent in:
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Product {
+    /// `G * H`: when one side is the same node and the other is adjacent.
+    /// Paths multiply into grids.
+    Cartesian,
+    /// `G & H`: when both sides are adjacent. Also known as the direct or
+    /// categorical product.
+    Tensor,
+    /// `G ** H`: when either product above has the edge. Paths multiply into
+    /// grids with diagonals.
+    Strong,
+}
+
+impl Product {
+    /// Returns the product of the two graphs.
+    pub fn apply(&self, g: &Graph, h: &Graph) -> Graph {
+        let mut product = Graph::new();
+        for a in g.nodes() {
+            for x in h.nodes() {
+                product.add_node(pair(a, x));
+            }
+        }
+        if matches!(self, Product::Cartesian | Product::Strong) {
+            for (a, b) in g.edges() {
+                for x in h.nodes() {
+                    product.add_edge(pair(a, x), pair(b, x));
+                }
+            }
+            for a in g.nodes() {
+                for (x, y) in h.edges() {
+                    product.add_edge(pair(a, x), pair(a, y));
+                }
+            }
+        }
+        if matches!(self, Product::Tensor | Product::Strong) {
+            for (a, b) in g.edges() {
+                for (x, y) in h.edges() {
+                    product.add_edge(pair(a, x), pair(b, y));
+                    product.add_edge(pair(a, y), pair(b, x));
+                }
+            }
+        }
+        product
+    }
+}
+
+impl fmt::Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Product::Cartesian => write!(f, "*"),
+            Product::Tensor => write!(f, "&"),
+            Product::Strong => write!(f, "**"),
+        }
+    }
+}
+
+/// The node of a product for a node from each side, named `(A, X)`.
+///
+/// Names which aren't identifiers are quoted as in expressions, so pairs of
+/// different nodes never share a name, e.g. `("A, B", C)` and `(A, "B, C")`.
+pub(crate) fn pair(a: &Node, x: &Node) -> Node {
+    let quote = |node: &Node| Expr::Node(node.clone()).to_string();
+    Node::from(format!("({}, {})", quote(a), quote(x)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Product;
+    use crate::{Expr, Graph, Node, Normalize, Parse};
+    use pretty_assertions::assert_eq;
+
+    fn product(s: &str) -> Graph {
+        Graph::from(&Expr::parse(s).unwrap())
+    }
+
+    #[test]
+    fn parse_product() {
+        let expr = Expr::parse("{A, B} * [X, Y] & Z ** W").unwrap();
+        assert_eq!(expr.to_string(), "{A, B} * [X, Y] & Z ** W");
+        assert!(matches!(expr, Expr::Product(Product::Strong, _, _)));
+        assert_eq!(Expr::parse("{A * B, C}").unwrap().to_string(), "{A * B, C}");
+    }
+
+    #[test]
+    fn product_nodes() {
+        let expr = Expr::parse("{A, B} * [X, Y, Z]").unwrap();
+        assert_eq!(expr.nodes().len(), 6);
+        assert!(expr.nodes().contains(&Node::from("(B, Z)")));
+        assert!(expr.contains(&Node::from("X")));
+        assert_eq!(expr.nodes(), expr.normalize().nodes());
+
+        // Components are quoted, so different pairs keep different names.
+        let left = Expr::parse(r#""A, B" * C"#).unwrap().nodes();
+        let right = Expr::parse(r#"A * "B, C""#).unwrap().nodes();
+        assert_eq!(left, vec![Node::from(r#"("A, B", C)"#)]);
+        assert_eq!(right, vec![Node::from(r#"(A, "B, C")"#)]);
+        let nested = Expr::parse("A * B * C").unwrap().nodes();
+        assert_eq!(nested, vec![Node::from(r#"("(A, B)", C)"#)]);
+    }
+
+    #[test]
+    fn cartesian_product() {
+        // A square is a path times an edge.
+        let square = product("{A, B} * {X, Y}");
+        assert_eq!(square.edge_count(), 4);
+        assert!(square.has_edge(&Node::from("(A, X)"), &Node::from("(A, Y)")));
+        assert!(!square.has_edge(&Node::from("(A, X)"), &Node::from("(B, Y)")));
+
+        // A 3-cube has 8 nodes of degree 3.
+        let cube = product("{A, B} * {C, D} * {E, F}");
+        assert_eq!(cube.node_count(), 8);
+        assert_eq!(cube.edge_count(), 12);
+        assert!(cube.nodes().all(|n| cube.degree(n) == 3));
+    }
+
+    #[test]
+    fn tensor_product() {
+        // Two edges multiply into two disjoint edges.
+        let graph = product("{A, B} & {X, Y}");
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.has_edge(&Node::from("(A, X)"), &Node::from("(B, Y)")));
+        assert!(graph.has_edge(&Node::from("(A, Y)"), &Node::from("(B, X)")));
+        assert_eq!(product("{A, B} & [X, Y]").edge_count(), 0);
+    }
+
+    #[test]
+    fn strong_product() {
+        // Cliques multiply into cliques.
+        assert_eq!(
+            Expr::parse("{A, B} ** {X, Y}").unwrap().normalize(),
+            Expr::parse(r#"{"(A, X)", "(A, Y)", "(B, X)", "(B, Y)"}"#).unwrap()
+        );
+        let grid = product("[{A, B}, {B, C}] ** [{X, Y}, {Y, Z}]");
+        assert_eq!(grid.node_count(), 9);
+        assert_eq!(grid.edge_count(), 20);
+    }
+
+    #[test]
+    fn normalize_product() {
+        assert_eq!(
+            Expr::parse("{A, B} * {X, Y}")
+                .unwrap()
+                .normalize()
+                .to_string(),
+            r#"[{"(A, X)", "(A, Y)"}, {"(A, X)", "(B, X)"}, {"(A, Y)", "(B, Y)"}, {"(B, X)", "(B, Y)"}]"#
+        );
+        assert_eq!(
+            Expr::parse("[A, B] & [X]").unwrap().normalize().to_string(),
+            r#"["(A, X)", "(B, X)"]"#
+        );
+        assert_eq!(
+            Expr::parse("A * []").unwrap().normalize(),
+            Expr::parse("[]").unwrap()
+        );
+    }
+}
@@ -6,8 +6,9 @@
 //! recursion. See [`Config`] and [`Env`] for more information on how this is
 //! handled.
 
+use crate::limit::{Budget, Limit, LimitExceeded, Limits};
 use crate::{Expr, Node, Ret, Stmt};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 /// Graph resolution configuration options.
@@ -22,6 +23,7 @@ pub struct Config {
     // struct Env(HashMap, Config, Recursion)
     // ```
     recursion: bool,
+    limits: Limits,
 }
 
 impl Default for Config {
@@ -29,6 +31,7 @@ impl Default for Config {
         Config {
             shadowing: false,
             recursion: false,
+            limits: Limits::default(),
         }
     }
 }
@@ -46,6 +49,22 @@ impl Config {
         self
     }
 
+    /// Bound the work done resolving each statement or expression.
+    ///
+    /// Resolution can grow exponentially since each reference is replaced by
+    /// a copy of its bound graph:
+    ///
+    /// ```grapl
+    /// G1 = {A, B}
+    /// G2 = {G1, G1}
+    /// G3 = {G2, G2}
+    /// ...
+    /// ```
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     #[allow(unused)]
     fn with_recursion(mut self) -> Self {
         self.recursion = true;
@@ -53,7 +72,7 @@ impl Config {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 /// Errors that can occur during resolution.
 pub enum Error {
     /// ```grapl
@@ -65,9 +84,21 @@ pub enum Error {
     /// G = {G, B}
     /// ```
     Recursion,
+    /// See [`Config::with_limits`].
+    LimitExceeded(Limit),
+}
+
+impl From<LimitExceeded> for Error {
+    fn from(LimitExceeded(limit): LimitExceeded) -> Self {
+        Error::LimitExceeded(limit)
+    }
 }
 
 /// Running resolution environment used to maintain state.
+///
+/// With the `serde` feature an environment serializes as a map from each bound
+/// node to its resolved expression, and is deserialized with
+/// [`Env::deserialize`] given a configuration.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Env<'cfg>(HashMap<Node, Expr>, &'cfg Config);
 
@@ -86,6 +117,35 @@ impl<'cfg> Env<'cfg> {
         }
     }
 
+    /// Create a resolution environment with the given resolved bindings.
+    ///
+    /// Each binding is inserted in order with [`Env::insert`], so the same
+    /// errors are returned.
+    pub fn from_bindings(
+        config: &'cfg Config,
+        bindings: impl IntoIterator<Item = (Node, Expr)>,
+    ) -> Result<Self, Error> {
+        let mut env = Env::new(config);
+        for (node, expr) in bindings {
+            env.insert(node, expr)?;
+        }
+        Ok(env)
+    }
+
+    /// Returns the bindings of this environment, sorted by node.
+    pub fn bindings(&self) -> impl Iterator<Item = (&Node, &Expr)> {
+        self.0.iter().collect::<BTreeMap<_, _>>().into_iter()
+    }
+
+    pub(crate) fn limits(&self) -> &Limits {
+        &self.1.limits
+    }
+
+    /// Returns the expression bound to the given node, if there is one.
+    pub fn get(&self, node: &Node) -> Option<&Expr> {
+        self.0.get(node)
+    }
+
     /// Inserts the given node's expression into this environment.
     ///
     /// This function returns an error when it detects [`Error::Shadowing`] or
@@ -103,10 +163,32 @@ impl<'cfg> Env<'cfg> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Env<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.bindings())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'cfg> Env<'cfg> {
+    /// Deserialize an environment's bindings with the given configuration.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        config: &'cfg Config,
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        use serde::Deserialize;
+        use serde::de::Error as _;
+
+        let bindings = BTreeMap::<Node, Expr>::deserialize(deserializer)?;
+        Env::from_bindings(config, bindings).map_err(|err| D::Error::custom(format!("{:?}", err)))
+    }
+}
+
 /// Implement Display for Env to show its contents.
 impl<'cfg> fmt::Display for Env<'cfg> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (node, expr) in &self.0 {
+        for (node, expr) in self.bindings() {
             writeln!(f, "{} = {}", node, expr)?;
         }
         Ok(())
@@ -134,20 +216,88 @@ impl<'src> Resolve<'src> for Expr {
     type Output = Self;
 
     fn resolve<'cfg>(&self, env: &mut Env<'cfg>) -> Result<Self::Output, Error> {
+        let config = env.1;
+        let budget = Budget::new(&config.limits);
+        let (expr, _) = self.resolve_within(env, &budget, 0)?;
+        Ok(expr)
+    }
+}
+
+impl Expr {
+    // Resolve this expression, also returning the number of nodes in the
+    // resolved expression so it can be checked against the budget.
+    pub(crate) fn resolve_within(
+        &self,
+        env: &Env,
+        budget: &Budget,
+        depth: usize,
+    ) -> Result<(Expr, usize), Error> {
+        budget.step()?;
+        budget.depth(depth)?;
+
         macro_rules! inner {
             ($exprs:expr, $variant:path) => {{
                 let mut fresh = vec![];
+                let mut size: usize = 0;
                 for expr in $exprs {
-                    fresh.push(expr.resolve(env)?);
+                    let (resolved, n) = expr.resolve_within(env, budget, depth + 1)?;
+                    size = size.saturating_add(n);
+                    budget.nodes(size)?;
+                    fresh.push(resolved);
                 }
-                Ok($variant(fresh))
+                Ok(($variant(fresh), size))
             }};
         }
 
         match self {
-            Expr::Node(node) => Ok(env.lookup(node)),
+            Expr::Node(node) => {
+                let size = env.0.get(node).map_or(1, Expr::size);
+                budget.nodes(size)?;
+                Ok((env.lookup(node), size))
+            }
             Expr::Connected(exprs) => inner!(exprs, Expr::Connected),
             Expr::Disconnected(exprs) => inner!(exprs, Expr::Disconnected),
+            Expr::Path(exprs) => inner!(exprs, Expr::Path),
+            Expr::Cycle(exprs) => inner!(exprs, Expr::Cycle),
+            Expr::Product(product, a, b) => {
+                let (a, m) = a.resolve_within(env, budget, depth + 1)?;
+                let (b, n) = b.resolve_within(env, budget, depth + 1)?;
+                let size = m.saturating_mul(n);
+                budget.nodes(size)?;
+                Ok((Expr::Product(*product, Box::new(a), Box::new(b)), size))
+            }
+            Expr::Subgraph(subgraph, a, b) => {
+                let (a, size) = a.resolve_within(env, budget, depth + 1)?;
+                let (b, _) = b.resolve_within(env, budget, depth + 1)?;
+                Ok((Expr::Subgraph(*subgraph, Box::new(a), Box::new(b)), size))
+            }
+            // Generators iterate over the nodes of their resolved graphs. The
+            // body is resolved before expanding, so generated nodes aren't
+            // looked up again, and its variables are hidden from the
+            // environment.
+            Expr::Comprehension(c) => {
+                let mut c = c.clone();
+                for source in c.sources_mut() {
+                    *source = source.resolve_within(env, budget, depth + 1)?.0;
+                }
+                let vars: Vec<_> = c.generators().iter().map(|(var, _)| var.clone()).collect();
+                let body = if vars.iter().any(|var| env.0.contains_key(var)) {
+                    let hidden = env
+                        .0
+                        .iter()
+                        .filter(|(node, _)| !vars.contains(node))
+                        .map(|(node, expr)| (node.clone(), expr.clone()))
+                        .collect();
+                    c.body()
+                        .resolve_within(&Env(hidden, env.1), budget, depth + 1)?
+                } else {
+                    c.body().resolve_within(env, budget, depth + 1)?
+                };
+                *c.body_mut() = body.0;
+                let expr = c.expand(budget)?;
+                let size = expr.size();
+                Ok((expr, size))
+            }
         }
     }
 }
@@ -191,7 +341,8 @@ impl<'src> Resolve<'src> for Ret {
 mod tests {
     use crate::{
         Expr, Node, Parse, Resolve, Ret, Stmt,
-        resolve::{Config, Env},
+        limit::{Limit, Limits},
+        resolve::{Config, Env, Error},
     };
     use pretty_assertions::assert_eq;
 
@@ -446,4 +597,57 @@ mod tests {
             .unwrap(),
         );
     }
+
+    #[test]
+    fn resolve_limits() {
+        let config = Config::default().with_limits(Limits::default().with_nodes(8));
+        let mut env = Env::new(&config);
+
+        assert_eq!(
+            Vec::<Stmt>::parse(
+                r#"
+                    G1 = {A, B}
+                    G2 = {G1, G1}
+                    G3 = {G2, G2}
+                    G4 = {G3, G3}
+                "#
+            )
+            .unwrap()
+            .resolve(&mut env)
+            .unwrap_err(),
+            Error::LimitExceeded(Limit::Nodes),
+        );
+
+        let config = Config::default().with_limits(Limits::default().with_depth(2));
+        let mut env = Env::new(&config);
+        assert_eq!(
+            Expr::parse("{A, [B, {C, D}]}")
+                .unwrap()
+                .resolve(&mut env)
+                .unwrap_err(),
+            Error::LimitExceeded(Limit::Depth),
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn env_serde() {
+        let config = Config::default();
+        let mut env = Env::new(&config);
+        Vec::<Stmt>::parse("G2 = {X, G1} G1 = [A, B]")
+            .unwrap()
+            .resolve(&mut env)
+            .unwrap();
+        let json = serde_json::to_string(&env).unwrap();
+        assert_eq!(
+            json,
+            r#"{"G1":{"disconnected":[{"node":"A"},{"node":"B"}]},"G2":{"connected":[{"node":"X"},{"node":"G1"}]}}"#
+        );
+
+        let mut de = serde_json::Deserializer::from_str(&json);
+        assert_eq!(Env::deserialize(&config, &mut de).unwrap(), env);
+
+        let mut de = serde_json::Deserializer::from_str(r#"{"G":{"connected":[{"node":"G"}]}}"#);
+        assert!(Env::deserialize(&config, &mut de).is_err());
+    }
 }
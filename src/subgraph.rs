@@ -0,0 +1,204 @@
+//! Subgraphs, which keep or remove part of another graph.
+//!
+//! The right side of a node selection is only used for its nodes, so it can be
+//! a single node, a list of nodes or another named graph:
+//!
+//! ```grapl
+//! G = {X, [A, B, C]}
+//! G | [A, B, X]
+//! => {X, [A, B]}
+//! G \ X
+//! => [A, B, C]
+//! ```
+//!
+//! Removing edges uses the edges of the right side instead, leaving the
+//! largest cliques of the remaining graph:
+//!
+//! ```grapl
+//! {A, B, C, D} - (A, D)
+//! => [{A, B, C}, {B, C, D}]
+//! ```
+
+use crate::graph::{self, Graph};
+use crate::limit::{Budget, LimitExceeded};
+use crate::{Expr, Node};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The ways part of a graph can be selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Subgraph {
+    /// `G | H`: the subgraph induced by the nodes of `H`, i.e. only those
+    /// nodes and the edges between them.
+    Induced,
+    /// `G \ H`: the nodes of `H` and all their edges are removed.
+    Without,
+    /// `G - H`: the edges of `H` are removed, keeping all the nodes of `G`.
+    Difference,
+}
+
+impl Subgraph {
+    /// Returns true if the node is kept, given the nodes of the right side.
+    fn keeps(&self, node: &Node, nodes: &BTreeSet<Node>) -> bool {
+        match self {
+            Subgraph::Induced => nodes.contains(node),
+            Subgraph::Without => !nodes.contains(node),
+            Subgraph::Difference => true,
+        }
+    }
+
+    /// Returns the nodes of the subgraph of `g` selected by the nodes of `h`.
+    pub(crate) fn nodes(
+        &self,
+        g: &Expr,
+        h: &Expr,
+        budget: &Budget,
+    ) -> Result<Vec<Node>, LimitExceeded> {
+        let nodes = h.nodes_within(budget)?.into_iter().collect();
+        Ok(g.nodes_within(budget)?
+            .into_iter()
+            .filter(|node| self.keeps(node, &nodes))
+            .collect())
+    }
+
+    /// Applies this selection to a normalized expression, returning the
+    /// cliques which are left.
+    ///
+    /// Every edge of a normalized expression is inside one of its cliques, so
+    /// removing nodes from each clique removes exactly their edges. Removing
+    /// edges breaks cliques apart, so the remaining graph is covered again.
+    pub(crate) fn apply(&self, g: &Expr, h: &Expr) -> Vec<Expr> {
+        if let Subgraph::Difference = self {
+            let mut graph = Graph::from(g);
+            for (a, b) in Graph::from(h).edges() {
+                graph.remove_edge(a, b);
+            }
+            return graph::cliques(&graph);
+        }
+        let nodes = h.nodes().into_iter().collect();
+        let cliques = match g {
+            Expr::Disconnected(exprs) => exprs.as_slice(),
+            e => std::slice::from_ref(e),
+        };
+        let mut ds = vec![];
+        for clique in cliques {
+            let mut cs: Vec<_> = clique
+                .nodes()
+                .into_iter()
+                .filter(|node| self.keeps(node, &nodes))
+                .map(Expr::Node)
+                .collect();
+            match cs.len() {
+                0 => {}
+                1 => ds.push(cs.remove(0)),
+                _ => ds.push(Expr::Connected(cs)),
+            }
+        }
+        ds
+    }
+}
+
+impl fmt::Display for Subgraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subgraph::Induced => write!(f, "|"),
+            Subgraph::Without => write!(f, "\\"),
+            Subgraph::Difference => write!(f, "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Subgraph;
+    use crate::normal::{self, Form};
+    use crate::resolve::{Config, Env};
+    use crate::{Expr, Node, Normalize, Parse, Resolve};
+    use pretty_assertions::assert_eq;
+
+    fn normalize(s: &str) -> String {
+        Expr::parse(s).unwrap().normalize().to_string()
+    }
+
+    #[test]
+    fn parse_subgraph() {
+        let expr = Expr::parse(r"{A, B, C} \ C | [A, B] * X").unwrap();
+        assert_eq!(expr.to_string(), r"{A, B, C} \ C | [A, B] * X");
+        assert!(matches!(
+            Expr::parse("G | A").unwrap(),
+            Expr::Subgraph(Subgraph::Induced, _, _)
+        ));
+        assert_eq!(
+            Expr::parse(r"G \ {A | B}").unwrap().to_string(),
+            r"G \ {A | B}"
+        );
+    }
+
+    #[test]
+    fn subgraph_nodes() {
+        let expr = Expr::parse(r"{X, [A, B]} \ [X, Y]").unwrap();
+        assert_eq!(expr.nodes(), vec![Node::from("A"), Node::from("B")]);
+        assert!(expr.contains(&Node::from("X")));
+        assert_eq!(
+            Expr::parse("{X, [A, B]} | [B, X, Y]").unwrap().nodes(),
+            vec![Node::from("B"), Node::from("X")]
+        );
+    }
+
+    #[test]
+    fn normalize_subgraph() {
+        assert_eq!(normalize(r"{A, B, C} \ C"), "{A, B}");
+        assert_eq!(normalize(r"{X, [A, B]} \ X"), "[A, B]");
+        assert_eq!(normalize(r"{X, [A, B]} \ A"), "{B, X}");
+        assert_eq!(normalize(r"{A, B} \ [A, B]"), "[]");
+        assert_eq!(normalize("{X, [A, B, C]} | [A, B, X]"), "[{A, X}, {B, X}]");
+        assert_eq!(normalize("(A, B, C, D)+ | [A, B, C]"), "[{A, B}, {B, C}]");
+        assert_eq!(normalize("{A, B} | C"), "[]");
+    }
+
+    #[test]
+    fn normalize_difference() {
+        assert_eq!(normalize("{A, B, C, D} - (A, D)"), "[{A, B, C}, {B, C, D}]");
+        assert_eq!(
+            Expr::parse("{A, B, C, D} - (A, D)")
+                .unwrap()
+                .normalize_with(&normal::Config::default().with_form(Form::Factored))
+                .to_string(),
+            "{B, C, [A, D]}"
+        );
+        assert_eq!(normalize("{X, [A, B]} - {X, A}"), "[{B, X}, A]");
+        assert_eq!(normalize("{A, B} - {A, B}"), "[A, B]");
+        assert_eq!(normalize("(A, B, C)+ - (A, B, C)"), "[{A, C}, B]");
+        assert_eq!(normalize("{A, B} - {C, D}"), "{A, B}");
+        assert_eq!(
+            Expr::parse("{A, B, C, D} - (A, D)").unwrap().nodes().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn resolve_subgraph() {
+        let config = Config::default();
+        let mut env = Env::new(&config);
+        let stmts = Vec::parse("G = {X, [A, B, C]} H = [A, B]").unwrap();
+        stmts.resolve(&mut env).unwrap();
+        assert_eq!(
+            Expr::parse(r"G \ H")
+                .unwrap()
+                .resolve(&mut env)
+                .unwrap()
+                .normalize(),
+            Expr::parse("{C, X}").unwrap()
+        );
+        assert_eq!(
+            Expr::parse("G | {H, X}")
+                .unwrap()
+                .resolve(&mut env)
+                .unwrap()
+                .normalize(),
+            Expr::parse("[{A, X}, {B, X}]").unwrap()
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! SVG drawings, without any external tools.
+//!
+//! Nodes are drawn as labeled circles at the positions given by a
+//! [`Layout`], and edges as straight lines between them.
+
+use crate::layout::Layout;
+use crate::{Expr, Graph};
+use std::fmt::Write;
+
+/// Pixels per layout unit.
+const SCALE: f64 = 60.0;
+/// Node circle radius in pixels.
+const RADIUS: f64 = 18.0;
+/// Space around the drawing in pixels.
+const MARGIN: f64 = 10.0;
+
+/// SVG output configuration options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    layout: Layout,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            layout: Layout::Force,
+        }
+    }
+}
+
+impl Config {
+    /// Select how nodes are positioned, force-directed by default.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+impl Expr {
+    /// Returns an SVG drawing of this expression's graph.
+    pub fn to_svg(&self) -> String {
+        self.to_svg_with(&Config::default())
+    }
+
+    /// Returns an SVG drawing of this expression's graph with the given
+    /// configuration.
+    pub fn to_svg_with(&self, config: &Config) -> String {
+        let graph = Graph::from(self);
+        let positions = config.layout.positions(&graph);
+
+        let (mut left, mut top) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut right, mut bottom) = (f64::NEG_INFINITY, f64::INFINITY);
+        for (x, y) in positions.values() {
+            left = left.min(*x);
+            right = right.max(*x);
+            top = top.max(*y);
+            bottom = bottom.min(*y);
+        }
+        let offset = RADIUS + MARGIN;
+        let (width, height) = if positions.is_empty() {
+            (2.0 * offset, 2.0 * offset)
+        } else {
+            (
+                (right - left) * SCALE + 2.0 * offset,
+                (top - bottom) * SCALE + 2.0 * offset,
+            )
+        };
+        // SVG's y axis points down.
+        let point = |(x, y): (f64, f64)| ((x - left) * SCALE + offset, (top - y) * SCALE + offset);
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}">"#,
+            w = width.ceil(),
+            h = height.ceil(),
+        )
+        .unwrap();
+        out.push_str("  <g stroke=\"black\">\n");
+        for (a, b) in graph.edges() {
+            let (x1, y1) = point(positions[a]);
+            let (x2, y2) = point(positions[b]);
+            writeln!(
+                out,
+                r#"    <line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}"/>"#,
+                x1, y1, x2, y2
+            )
+            .unwrap();
+        }
+        out.push_str("  </g>\n");
+        out.push_str(
+            "  <g fill=\"white\" stroke=\"black\" font-family=\"sans-serif\" font-size=\"12\" text-anchor=\"middle\">\n",
+        );
+        for (node, position) in &positions {
+            let (x, y) = point(*position);
+            writeln!(
+                out,
+                r#"    <circle cx="{:.1}" cy="{:.1}" r="{}"/>"#,
+                x, y, RADIUS
+            )
+            .unwrap();
+            writeln!(
+                out,
+                r#"    <text x="{:.1}" y="{:.1}" fill="black" stroke="none" dominant-baseline="central">{}</text>"#,
+                x,
+                y,
+                escape(node.as_str())
+            )
+            .unwrap();
+        }
+        out.push_str("  </g>\n");
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::layout::Layout;
+    use crate::{Expr, Graph, Node, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_svg() {
+        let svg = Expr::parse("[{A, B}, {B, C}, {C, D}]").unwrap().to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<line ").count(), 3);
+        assert_eq!(svg.matches("<circle ").count(), 4);
+        assert!(svg.ends_with("</svg>\n"));
+
+        let graph: Graph = [(Node::from("A"), Node::from("<B>"))].into_iter().collect();
+        assert!(Expr::from(&graph).to_svg().contains(">&lt;B&gt;</text>"));
+    }
+
+    #[test]
+    fn to_svg_with() {
+        let config = Config::default().with_layout(Layout::Layered);
+        assert_eq!(
+            Expr::parse("{A, B}").unwrap().to_svg_with(&config),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="56" height="146" viewBox="0 0 56 146">
+  <g stroke="black">
+    <line x1="28.0" y1="28.0" x2="28.0" y2="118.0"/>
+  </g>
+  <g fill="white" stroke="black" font-family="sans-serif" font-size="12" text-anchor="middle">
+    <circle cx="28.0" cy="28.0" r="18"/>
+    <text x="28.0" y="28.0" fill="black" stroke="none" dominant-baseline="central">A</text>
+    <circle cx="28.0" cy="118.0" r="18"/>
+    <text x="28.0" y="118.0" fill="black" stroke="none" dominant-baseline="central">B</text>
+  </g>
+</svg>
+"#
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Adjacency tables for viewing graphs in a terminal.
+//!
+//! ```grapl
+//! {X, [A, B]}
+//! => to_table
+//!   │ A B X
+//! ──┼──────
+//! A │ · · ●
+//! B │ · · ●
+//! X │ ● ● ·
+//! ```
+
+use crate::{Expr, Graph};
+use std::fmt::Write;
+
+/// Adjacency table output configuration options.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    ascii: bool,
+}
+
+impl Config {
+    /// Only use ASCII characters, for terminals without Unicode support.
+    pub fn with_ascii(mut self) -> Self {
+        self.ascii = true;
+        self
+    }
+}
+
+struct Symbols {
+    vertical: char,
+    horizontal: char,
+    cross: char,
+    edge: char,
+    none: char,
+}
+
+const UNICODE: Symbols = Symbols {
+    vertical: '│',
+    horizontal: '─',
+    cross: '┼',
+    edge: '●',
+    none: '·',
+};
+
+const ASCII: Symbols = Symbols {
+    vertical: '|',
+    horizontal: '-',
+    cross: '+',
+    edge: 'x',
+    none: '.',
+};
+
+impl Expr {
+    /// Returns the adjacency table of this expression's graph.
+    pub fn to_table(&self) -> String {
+        self.to_table_with(&Config::default())
+    }
+
+    /// Returns the adjacency table of this expression's graph with the given
+    /// configuration.
+    ///
+    /// Rows and columns are both ordered by [`Expr::nodes`], and each column
+    /// is as wide as its node's name.
+    pub fn to_table_with(&self, config: &Config) -> String {
+        let symbols = if config.ascii { &ASCII } else { &UNICODE };
+        let graph = Graph::from(self);
+        let nodes: Vec<_> = graph.nodes().collect();
+        let widths: Vec<_> = nodes.iter().map(|n| n.as_str().chars().count()).collect();
+        let label = match widths.iter().max() {
+            Some(label) => *label,
+            None => return String::new(),
+        };
+
+        let mut out = String::new();
+        write!(out, "{:label$} {}", "", symbols.vertical).unwrap();
+        for node in &nodes {
+            write!(out, " {}", node).unwrap();
+        }
+        out.push('\n');
+
+        let line = |n| std::iter::repeat_n(symbols.horizontal, n).collect::<String>();
+        let total = widths.iter().map(|w| w + 1).sum();
+        writeln!(out, "{}{}{}", line(label + 1), symbols.cross, line(total)).unwrap();
+
+        for a in &nodes {
+            let mut row = format!("{:<label$} {}", a.as_str(), symbols.vertical);
+            for (b, width) in nodes.iter().zip(&widths) {
+                let cell = if graph.has_edge(a, b) {
+                    symbols.edge
+                } else {
+                    symbols.none
+                };
+                write!(row, " {:^width$}", cell).unwrap();
+            }
+            writeln!(out, "{}", row.trim_end()).unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::{Expr, Parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_table() {
+        assert_eq!(Expr::parse("[]").unwrap().to_table(), "");
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_table(),
+            "  │ A B X
+──┼──────
+A │ · · ●
+B │ · · ●
+X │ ● ● ·
+"
+        );
+    }
+
+    #[test]
+    fn to_table_with() {
+        assert_eq!(
+            Expr::parse("[{web, db}, cache]")
+                .unwrap()
+                .to_table_with(&Config::default().with_ascii()),
+            "      | cache db web
+------+-------------
+cache |   .   .   .
+db    |   .   .   x
+web   |   .   x   .
+"
+        );
+    }
+}
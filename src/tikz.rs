@@ -0,0 +1,260 @@
+//! TikZ and LaTeX output for papers.
+//!
+//! ```grapl
+//! [A, B]
+//! => to_tikz
+//! \begin{tikzpicture}
+//!   \node (n0) at (0.00, 1.00) {$A$};
+//!   \node (n1) at (0.00, -1.00) {$B$};
+//! \end{tikzpicture}
+//! ```
+
+use crate::layout::Layout;
+use crate::{Condition, Expr, Graph, Node, Normalize, Product, Subgraph};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// TikZ output configuration options.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    layout: Layout,
+    math: bool,
+}
+
+impl Config {
+    /// Select how nodes are positioned, circular by default.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Typeset the normalized expression below the picture.
+    pub fn with_math(mut self) -> Self {
+        self.math = true;
+        self
+    }
+}
+
+impl Expr {
+    /// Returns a `tikzpicture` of this expression's graph.
+    pub fn to_tikz(&self) -> String {
+        self.to_tikz_with(&Config::default())
+    }
+
+    /// Returns a `tikzpicture` of this expression's graph with the given
+    /// configuration.
+    pub fn to_tikz_with(&self, config: &Config) -> String {
+        let graph = Graph::from(self);
+        let positions = config.layout.positions(&graph);
+        let ids: BTreeMap<&Node, String> = graph
+            .nodes()
+            .enumerate()
+            .map(|(i, node)| (node, format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("\\begin{tikzpicture}\n");
+        for (node, (x, y)) in &positions {
+            writeln!(
+                out,
+                "  \\node ({}) at ({}, {}) {{${}$}};",
+                ids[node],
+                coordinate(*x),
+                coordinate(*y),
+                escape(node.as_str())
+            )
+            .unwrap();
+        }
+        for (a, b) in graph.edges() {
+            writeln!(out, "  \\draw ({}) -- ({});", ids[a], ids[b]).unwrap();
+        }
+        if config.math {
+            writeln!(
+                out,
+                "  \\node[below] at (current bounding box.south) {{${}$}};",
+                self.normalize().to_latex()
+            )
+            .unwrap();
+        }
+        out.push_str("\\end{tikzpicture}\n");
+        out
+    }
+
+    /// Returns this expression in LaTeX math notation, without the
+    /// surrounding `$`.
+    ///
+    /// ```grapl
+    /// {X, [A, B]}
+    /// => to_latex
+    /// \{X, [A, B]\}
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let joined = |exprs: &[Expr]| {
+            exprs
+                .iter()
+                .map(Expr::to_latex)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Expr::Node(node) => escape(node.as_str()),
+            Expr::Connected(exprs) => format!("\\{{{}\\}}", joined(exprs)),
+            Expr::Disconnected(exprs) => format!("[{}]", joined(exprs)),
+            Expr::Path(exprs) => format!("({})", joined(exprs)),
+            Expr::Cycle(exprs) => format!("({})^{{+}}", joined(exprs)),
+            Expr::Product(_, a, b) | Expr::Subgraph(_, a, b) => {
+                let op = match self {
+                    Expr::Product(Product::Cartesian, ..) => "\\square",
+                    Expr::Product(Product::Tensor, ..) => "\\times",
+                    Expr::Product(Product::Strong, ..) => "\\boxtimes",
+                    Expr::Subgraph(Subgraph::Induced, ..) => "\\vert",
+                    Expr::Subgraph(Subgraph::Without, ..) => "\\setminus",
+                    _ => "-",
+                };
+                // Nested operators on the right are grouped in a clique, as
+                // they're printed.
+                match **b {
+                    Expr::Product(..) | Expr::Subgraph(..) => {
+                        format!("{} {} \\{{{}\\}}", a.to_latex(), op, b.to_latex())
+                    }
+                    _ => format!("{} {} {}", a.to_latex(), op, b.to_latex()),
+                }
+            }
+            // Set-builder notation, e.g. [x \mid x \in G, x \neq A]
+            Expr::Comprehension(c) => {
+                let mut terms: Vec<_> = c
+                    .generators()
+                    .iter()
+                    .map(|(var, source)| {
+                        format!("{} \\in {}", escape(var.as_str()), source.to_latex())
+                    })
+                    .collect();
+                terms.extend(c.conditions().iter().map(|condition| {
+                    let (a, op, b) = match condition {
+                        Condition::Eq(a, b) => (a, "=", b),
+                        Condition::Ne(a, b) => (a, "\\neq", b),
+                        Condition::Lt(a, b) => (a, "<", b),
+                    };
+                    format!("{} {} {}", escape(a.as_str()), op, escape(b.as_str()))
+                }));
+                let (open, close) = if c.is_connected() {
+                    ("\\{", "\\}")
+                } else {
+                    ("[", "]")
+                };
+                format!(
+                    "{}{} \\mid {}{}",
+                    open,
+                    c.body().to_latex(),
+                    terms.join(", "),
+                    close
+                )
+            }
+        }
+    }
+}
+
+// Fixed precision keeps the output stable, without a negative zero.
+fn coordinate(x: f64) -> String {
+    let x = (x * 100.0).round() / 100.0;
+    format!("{:.2}", if x == 0.0 { 0.0 } else { x })
+}
+
+// Escapes characters which are special in LaTeX math mode.
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '_' | '#' | '$' | '%' | '&' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\backslash "),
+            '^' => escaped.push_str("\\hat{}"),
+            '~' => escaped.push_str("\\sim "),
+            ' ' => escaped.push_str("\\ "),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+    use crate::layout::Layout;
+    use crate::{Expr, Graph, Node, Parse, Product};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_tikz() {
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_tikz(),
+            r"\begin{tikzpicture}
+  \node (n0) at (0.00, 1.00) {$A$};
+  \node (n1) at (0.87, -0.50) {$B$};
+  \node (n2) at (-0.87, -0.50) {$X$};
+  \draw (n0) -- (n2);
+  \draw (n1) -- (n2);
+\end{tikzpicture}
+"
+        );
+    }
+
+    #[test]
+    fn to_tikz_with() {
+        let config = Config::default().with_layout(Layout::Layered).with_math();
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_tikz_with(&config),
+            r"\begin{tikzpicture}
+  \node (n0) at (0.00, 0.00) {$A$};
+  \node (n1) at (0.00, -3.00) {$B$};
+  \node (n2) at (0.00, -1.50) {$X$};
+  \draw (n0) -- (n2);
+  \draw (n1) -- (n2);
+  \node[below] at (current bounding box.south) {$[\{X, A\}, \{X, B\}]$};
+\end{tikzpicture}
+"
+        );
+    }
+
+    #[test]
+    fn to_latex() {
+        assert_eq!(
+            Expr::parse("{X, [A, B]}").unwrap().to_latex(),
+            r"\{X, [A, B]\}"
+        );
+        let graph: Graph = [(Node::from("web_1"), Node::from("a&b"))]
+            .into_iter()
+            .collect();
+        assert_eq!(Expr::from(&graph).to_latex(), r"\{a\&b, web\_1\}");
+        assert_eq!(
+            Expr::parse("A * B & {C ** D}").unwrap().to_latex(),
+            r"A \square B \times \{C \boxtimes D\}"
+        );
+        assert_eq!(
+            Expr::parse("A * {B * C}").unwrap().to_latex(),
+            r"A \square \{B \square C\}"
+        );
+        assert_eq!(
+            Expr::parse(r"G | [A, B] \ {A * B}").unwrap().to_latex(),
+            r"G \vert [A, B] \setminus \{A \square B\}"
+        );
+        assert_eq!(
+            Expr::parse("{A, B, C} - (A, B)").unwrap().to_latex(),
+            r"\{A, B, C\} - (A, B)"
+        );
+        let nested = Expr::Product(
+            Product::Cartesian,
+            Box::new(Expr::parse("A").unwrap()),
+            Box::new(Expr::parse("B * C").unwrap()),
+        );
+        assert_eq!(nested.to_string(), "A * {B * C}");
+        assert_eq!(nested.to_latex(), r"A \square \{B \square C\}");
+        assert_eq!(
+            Expr::parse("{{x, y} for x in G, y in [A, B] if x != y}")
+                .unwrap()
+                .to_latex(),
+            r"\{\{x, y\} \mid x \in G, y \in [A, B], x \neq y\}"
+        );
+    }
+}
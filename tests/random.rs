@@ -1,4 +1,5 @@
-use grapl::Normalize;
+use grapl::normal::{Config, Form};
+use grapl::{Expr, Graph, Normalize};
 use rand::Rng;
 
 #[path = "test_helper.rs"]
@@ -20,6 +21,29 @@ fn random_nodes_and_edges() {
     }
 }
 
+#[test]
+fn random_factored_nodes_and_edges() {
+    let config = Config::default().with_form(Form::Factored).with_sorting();
+    for _ in 0..ITERATIONS {
+        let depth = rand::rng().random_range(0..25);
+        let expr = generate_expr(25, depth, 10, 10);
+        let factored = expr.normalize_with(&config);
+        assert_eq!(expr.nodes(), factored.nodes());
+        assert_eq!(expr.edges(), factored.edges());
+    }
+}
+
+#[test]
+fn random_graph_round_trip() {
+    for _ in 0..ITERATIONS {
+        let depth = rand::rng().random_range(0..10);
+        let expr = generate_expr(25, depth, 10, 10);
+        let rebuilt = Expr::from(&Graph::from(&expr));
+        assert_eq!(expr.nodes(), rebuilt.nodes());
+        assert_eq!(expr.edges(), rebuilt.edges());
+    }
+}
+
 fn random_display_ratio(max_depth: usize, cweight: usize, dweight: usize) -> f64 {
     let mut len = 0;
     let mut norm_len = 0;